@@ -12,15 +12,26 @@ mod udp_nal;
 
 mod seccontext;
 
+const BUFFER_SIZE: usize = riot_rs::utils::usize_from_env_or!(
+    "CONFIG_UDP_BUFFER_SIZE",
+    4096,
+    "size of the UDP socket buffers (in bytes)"
+);
+const METADATA_NUMOF: usize = riot_rs::utils::usize_from_env_or!(
+    "CONFIG_UDP_METADATA_NUMOF",
+    16,
+    "number of packets the UDP socket buffers can hold"
+);
+
 #[riot_rs::task(autostart)]
 async fn coap_run() {
     let stack = network::network_stack().await.unwrap();
 
     // FIXME trim to CoAP requirements
-    let mut rx_meta = [PacketMetadata::EMPTY; 16];
-    let mut rx_buffer = [0; 4096];
-    let mut tx_meta = [PacketMetadata::EMPTY; 16];
-    let mut tx_buffer = [0; 4096];
+    let mut rx_meta = [PacketMetadata::EMPTY; METADATA_NUMOF];
+    let mut rx_buffer = [0; BUFFER_SIZE];
+    let mut tx_meta = [PacketMetadata::EMPTY; METADATA_NUMOF];
+    let mut tx_buffer = [0; BUFFER_SIZE];
 
     let socket = UdpSocket::new(
         stack,
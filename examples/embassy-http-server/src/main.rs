@@ -48,7 +48,21 @@ impl picoserve::extract::FromRef<AppState> for ButtonInputs {
 
 type AppRouter = impl picoserve::routing::PathRouter<AppState>;
 
-const WEB_TASK_POOL_SIZE: usize = 2;
+const WEB_TASK_POOL_SIZE: usize = riot_rs::utils::usize_from_env_or!(
+    "CONFIG_WEB_TASK_POOL_SIZE",
+    2,
+    "number of concurrent HTTP connections"
+);
+const TCP_BUFFER_SIZE: usize = riot_rs::utils::usize_from_env_or!(
+    "CONFIG_TCP_BUFFER_SIZE",
+    1024,
+    "size of the TCP socket buffers of each connection (in bytes)"
+);
+const HTTP_BUFFER_SIZE: usize = riot_rs::utils::usize_from_env_or!(
+    "CONFIG_HTTP_BUFFER_SIZE",
+    2048,
+    "size of the HTTP request buffer of each connection (in bytes)"
+);
 
 #[riot_rs::task(pool_size = WEB_TASK_POOL_SIZE)]
 async fn web_task(
@@ -59,8 +73,9 @@ async fn web_task(
 ) -> ! {
     let stack = network::network_stack().await.unwrap();
 
-    let mut rx_buffer = [0; 1024];
-    let mut tx_buffer = [0; 1024];
+    let mut rx_buffer = [0; TCP_BUFFER_SIZE];
+    let mut tx_buffer = [0; TCP_BUFFER_SIZE];
+    let mut http_buffer = [0; HTTP_BUFFER_SIZE];
 
     loop {
         let mut socket = TcpSocket::new(stack, &mut rx_buffer, &mut tx_buffer);
@@ -75,7 +90,7 @@ async fn web_task(
 
         println!("{}: Received connection from {:?}", id, remote_endpoint);
 
-        match picoserve::serve_with_state(app, config, &mut [0; 2048], socket, &state).await {
+        match picoserve::serve_with_state(app, config, &mut http_buffer, socket, &state).await {
             Ok(handled_requests_count) => {
                 println!(
                     "{} requests handled from {:?}",
@@ -144,17 +159,7 @@ fn network_config() -> embassy_net::Config {
 #[cfg(capability = "hw/usb-device-port")]
 #[riot_rs::config(usb)]
 fn usb_config() -> riot_rs::embassy::embassy_usb::Config<'static> {
-    let mut config = riot_rs::embassy::embassy_usb::Config::new(0xc0de, 0xcafe);
-    config.manufacturer = Some("Embassy");
+    let mut config = riot_rs::embassy::usb::default_config();
     config.product = Some("HTTP-over-USB-Ethernet example");
-    config.serial_number = Some("12345678");
-    config.max_power = 100;
-    config.max_packet_size_0 = 64;
-
-    // Required for Windows support.
-    config.composite_with_iads = true;
-    config.device_class = 0xEF;
-    config.device_sub_class = 0x02;
-    config.device_protocol = 0x01;
     config
 }
@@ -7,14 +7,20 @@ use riot_rs::{debug::println, embassy::network};
 
 use embedded_io_async::Write;
 
+const BUFFER_SIZE: usize = riot_rs::utils::usize_from_env_or!(
+    "CONFIG_TCP_BUFFER_SIZE",
+    4096,
+    "size of the TCP socket and echo buffers (in bytes)"
+);
+
 #[riot_rs::task(autostart)]
 async fn tcp_echo() {
     use embassy_net::tcp::TcpSocket;
     let stack = network::network_stack().await.unwrap();
 
-    let mut rx_buffer = [0; 4096];
-    let mut tx_buffer = [0; 4096];
-    let mut buf = [0; 4096];
+    let mut rx_buffer = [0; BUFFER_SIZE];
+    let mut tx_buffer = [0; BUFFER_SIZE];
+    let mut buf = [0; BUFFER_SIZE];
 
     loop {
         let mut socket = TcpSocket::new(stack, &mut rx_buffer, &mut tx_buffer);
@@ -0,0 +1,38 @@
+#![no_std]
+#![no_main]
+#![feature(type_alias_impl_trait)]
+#![feature(used_with_arg)]
+
+use riot_rs::{
+    debug::println,
+    embassy::{arch, uart, usb::uart_bridge::UartBridge},
+};
+
+mod pins;
+
+#[riot_rs::task(autostart, peripherals, usb_builder_hook)]
+async fn usb_uart_bridge(peripherals: pins::UartPeripherals) {
+    let config = uart::Config::default();
+
+    #[cfg(context = "nrf")]
+    let uart = arch::uart::new(
+        peripherals.uart,
+        peripherals.timer,
+        peripherals.ppi_ch1,
+        peripherals.ppi_ch2,
+        peripherals.ppi_group,
+        peripherals.rx,
+        peripherals.tx,
+        &config,
+    )
+    .unwrap();
+    #[cfg(context = "rp")]
+    let uart = arch::uart::new(peripherals.uart, peripherals.rx, peripherals.tx, &config).unwrap();
+
+    let bridge = USB_BUILDER_HOOK
+        .with(|builder| UartBridge::new(builder))
+        .await;
+
+    println!("Bridging the UART to USB");
+    bridge.run(uart).await
+}
@@ -0,0 +1,33 @@
+use riot_rs::embassy::arch::peripherals;
+
+// Pins D0 (RX) and D1 (TX) of the Arduino header.
+#[cfg(builder = "nrf52840dk")]
+riot_rs::define_peripherals!(UartPeripherals {
+    uart: UARTE0,
+    timer: TIMER1,
+    ppi_ch1: PPI_CH0,
+    ppi_ch2: PPI_CH1,
+    ppi_group: PPI_GROUP0,
+    rx: P1_01,
+    tx: P1_02,
+});
+
+// Pins D0 (RX) and D1 (TX) of the Arduino header.
+#[cfg(builder = "nrf5340dk")]
+riot_rs::define_peripherals!(UartPeripherals {
+    uart: SERIAL2,
+    timer: TIMER2,
+    ppi_ch1: PPI_CH0,
+    ppi_ch2: PPI_CH1,
+    ppi_group: PPI_GROUP0,
+    rx: P1_00,
+    tx: P1_01,
+});
+
+// Pins GP1 (RX) and GP0 (TX).
+#[cfg(builder = "rpi-pico")]
+riot_rs::define_peripherals!(UartPeripherals {
+    uart: UART0,
+    rx: PIN_1,
+    tx: PIN_0,
+});
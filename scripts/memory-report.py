@@ -0,0 +1,220 @@
+#!/usr/bin/env python3
+"""Prints per-crate flash and RAM usage of a linked ELF file.
+
+Usage: memory-report.py [--check] <elf> [<crate>:<flash|ram>=<max bytes> ...]
+
+Symbol sizes are attributed to the crate their (demangled) path starts with.
+Instances of generic items of the standard library crates (e.g.,
+`core::ptr::drop_in_place<riot_rs_embassy::Foo>`) are attributed to the first
+other crate among their generic parameters instead.
+The RAM taken by each linker section, and the static RAM used by each RIOT-rs
+subsystem (see `SUBSYSTEMS`), are reported as well.
+Each optional limit fails the report (exit code 1) if the crate uses more than
+the given number of bytes of flash or RAM, e.g., `riot_rs_embassy:ram=4096`.
+Limits can also be passed through the `MEMORY_REPORT_LIMITS` environment
+variable, separated by whitespace.
+
+With `--check`, only exceeded limits are printed, and nothing is done when no
+limits are given. This is run by the laze `LINK` rule after each build, with
+the limits from the `MEMORY_REPORT_LIMITS` laze variable.
+
+The `nm` tool used can be overridden through the `NM` environment variable; it
+needs to support demangling Rust symbols (e.g., `rust-nm` or `llvm-nm`). The
+`llvm-nm` of the `llvm-tools` rustup component is used if none is in `PATH`.
+"""
+
+import os
+import re
+import shutil
+import struct
+import subprocess
+import sys
+from collections import defaultdict
+
+# nm symbol types, see `man nm`
+FLASH_TYPES = set("TtRrDd")
+RAM_TYPES = set("DdBb")
+
+# Subsystems and the path prefixes (or infixes, starting with `::`) of their
+# statics, in matching order.
+SUBSYSTEMS = [
+    ("usb", ("riot_rs_embassy::usb", "embassy_usb")),
+    ("network", ("riot_rs_embassy::network", "embassy_net", "smoltcp")),
+    ("wifi", ("riot_rs_embassy::wifi", "cyw43", "esp_wifi")),
+    ("threads", ("riot_rs_threads", "::__start_thread_")),
+    ("executor", ("riot_rs_embassy::executor_swi", "riot_rs_embassy::EXECUTOR", "embassy_executor")),
+    ("time", ("embassy_time",)),
+    ("rt", ("riot_rs_rt",)),
+]
+
+# ELF section header flags
+SHF_WRITE = 0x1
+SHF_ALLOC = 0x2
+
+STD_CRATES = {"core", "alloc", "std"}
+
+# Start of a path, i.e., a crate name
+CRATE_RE = re.compile(r"(?<![\w:])([A-Za-z_]\w*)::")
+
+LIMIT_RE = re.compile(r"^(?P<crate>[\w\[\]]+):(?P<kind>flash|ram)=(?P<max>\d+)$")
+
+
+def find_nm():
+    if "NM" in os.environ:
+        return os.environ["NM"]
+    for candidate in ("rust-nm", "llvm-nm"):
+        if shutil.which(candidate):
+            return candidate
+    sysroot = subprocess.run(
+        ["rustc", "--print", "sysroot"], capture_output=True, text=True
+    ).stdout.strip()
+    host = re.search(
+        r"^host: (.*)$",
+        subprocess.run(["rustc", "-vV"], capture_output=True, text=True).stdout,
+        re.MULTILINE,
+    )
+    if sysroot and host:
+        candidate = os.path.join(sysroot, "lib", "rustlib", host[1], "bin", "llvm-nm")
+        if os.path.exists(candidate):
+            return candidate
+    sys.exit("error: no suitable nm found, please set NM")
+
+
+def crate_of(symbol):
+    # `<T as Trait>::method` is attributed to the crate of `T`, as its path
+    # comes first.
+    crates = CRATE_RE.findall(symbol)
+    if not crates:
+        return "[other]"
+    if crates[0] in STD_CRATES:
+        for crate in crates:
+            if crate not in STD_CRATES:
+                return crate
+    return crates[0]
+
+
+def subsystem_of(symbol):
+    path = symbol.lstrip("<&*")
+    for subsystem, patterns in SUBSYSTEMS:
+        for pattern in patterns:
+            if path.startswith(pattern) or (pattern.startswith("::") and pattern in path):
+                return subsystem
+    return "[other]"
+
+
+def ram_sections(elf):
+    """Returns the name and size of the writable sections of `elf` loaded in RAM."""
+    with open(elf, "rb") as f:
+        data = f.read()
+    is_64 = data[4] == 2
+    endian = "<" if data[5] == 1 else ">"
+    if is_64:
+        shoff, = struct.unpack_from(endian + "Q", data, 0x28)
+        shentsize, shnum, shstrndx = struct.unpack_from(endian + "HHH", data, 0x3A)
+        header = endian + "IIQQQQIIQQ"
+    else:
+        shoff, = struct.unpack_from(endian + "I", data, 0x20)
+        shentsize, shnum, shstrndx = struct.unpack_from(endian + "HHH", data, 0x2E)
+        header = endian + "IIIIIIIIII"
+
+    headers = [
+        struct.unpack_from(header, data, shoff + i * shentsize) for i in range(shnum)
+    ]
+    strtab_offset = headers[shstrndx][4]
+
+    sections = []
+    for name, _type, flags, _addr, _offset, size, *_ in headers:
+        if flags & (SHF_WRITE | SHF_ALLOC) == SHF_WRITE | SHF_ALLOC and size > 0:
+            start = strtab_offset + name
+            name = data[start : data.index(b"\0", start)].decode()
+            sections.append((name, size))
+    return sections
+
+
+def collect(elf):
+    usage = defaultdict(lambda: {"flash": 0, "ram": 0})
+    subsystems = defaultdict(int)
+    output = subprocess.run(
+        [find_nm(), "--print-size", "--demangle", elf],
+        check=True,
+        capture_output=True,
+        text=True,
+    ).stdout
+    for line in output.splitlines():
+        fields = line.split(maxsplit=3)
+        if len(fields) != 4:
+            # symbols without size
+            continue
+        _addr, size, kind, symbol = fields
+        size = int(size, 16)
+        crate = crate_of(symbol)
+        if kind in FLASH_TYPES:
+            usage[crate]["flash"] += size
+        if kind in RAM_TYPES:
+            usage[crate]["ram"] += size
+            subsystems[subsystem_of(symbol)] += size
+    return usage, subsystems
+
+
+def parse_limits(args):
+    limits = []
+    for arg in args:
+        match = LIMIT_RE.match(arg)
+        if not match:
+            sys.exit(f"error: invalid limit `{arg}`")
+        limits.append((match["crate"], match["kind"], int(match["max"])))
+    return limits
+
+
+def print_report(elf, usage, subsystems):
+    print(f"{'crate':<32} {'flash':>10} {'ram':>10}")
+    for crate, sizes in sorted(usage.items(), key=lambda item: -item[1]["flash"]):
+        print(f"{crate:<32} {sizes['flash']:>10} {sizes['ram']:>10}")
+
+    print()
+    print(f"{'RAM section':<32} {'ram':>10}")
+    for name, size in ram_sections(elf):
+        print(f"{name:<32} {size:>10}")
+
+    print()
+    print(f"{'subsystem (static RAM)':<32} {'ram':>10}")
+    for subsystem, size in sorted(subsystems.items(), key=lambda item: -item[1]):
+        print(f"{subsystem:<32} {size:>10}")
+
+
+
+def main():
+    args = sys.argv[1:]
+    check = args[:1] == ["--check"]
+    if check:
+        args = args[1:]
+    if not args:
+        sys.exit(__doc__)
+
+    elf = args[0]
+    limits = parse_limits(
+        args[1:] + os.environ.get("MEMORY_REPORT_LIMITS", "").split()
+    )
+    if check and not limits:
+        return
+
+    usage, subsystems = collect(elf)
+
+    if not check:
+        print_report(elf, usage, subsystems)
+
+    failed = False
+    for crate, kind, max_bytes in limits:
+        used = usage[crate][kind] if crate in usage else 0
+        if used > max_bytes:
+            print(
+                f"error: {elf}: {crate} uses {used} bytes of {kind}, limit is {max_bytes}",
+                file=sys.stderr,
+            )
+            failed = True
+
+    sys.exit(1 if failed else 0)
+
+
+if __name__ == "__main__":
+    main()
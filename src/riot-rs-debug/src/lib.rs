@@ -1,6 +1,12 @@
 #![cfg_attr(not(test), no_std)]
 #![cfg_attr(test, no_main)]
 
+#[cfg(feature = "threading")]
+mod threads;
+
+#[cfg(feature = "threading")]
+pub use threads::dump_tasks;
+
 #[cfg(all(feature = "rtt-target", feature = "cortex-m-semihosting"))]
 compile_error!("feature \"rtt-target\" and feature \"cortex-m-semihosting\" cannot be enabled at the same time");
 
@@ -0,0 +1,26 @@
+use riot_rs_threads::{thread_info, ThreadId, THREADS_NUMOF};
+
+use crate::println;
+
+/// Prints the state of all existing threads.
+///
+/// This is meant as a diagnostic aid, e.g., to find out which threads are blocked when the
+/// system appears to be deadlocked.
+/// Debuggers can obtain the same information from a halted target through
+/// [`RIOT_RS_THREADS_DEBUG_TABLE`](riot_rs_threads::RIOT_RS_THREADS_DEBUG_TABLE).
+pub fn dump_tasks() {
+    println!("pid | prio | flags  | sp         | state");
+    for pid in 0..THREADS_NUMOF {
+        let Some(info) = thread_info(ThreadId::new(pid as u8)) else {
+            continue;
+        };
+        println!(
+            "{:>3} | {:>4} | 0x{:04x} | 0x{:08x} | {:?}",
+            usize::from(info.pid),
+            usize::from(info.prio),
+            info.flags,
+            info.sp,
+            info.state,
+        );
+    }
+}
@@ -0,0 +1,31 @@
+use embedded_hal_async::i2c::{ErrorKind, ErrorType, I2c as I2cTrait, Operation};
+
+use crate::i2c::Config;
+
+pub struct I2c;
+
+pub type Error = ErrorKind;
+
+impl ErrorType for I2c {
+    type Error = Error;
+}
+
+impl From<Error> for crate::i2c::Error {
+    fn from(kind: Error) -> Self {
+        Self::Bus(kind)
+    }
+}
+
+impl I2cTrait for I2c {
+    async fn transaction(
+        &mut self,
+        _address: u8,
+        _operations: &mut [Operation<'_>],
+    ) -> Result<(), Self::Error> {
+        unimplemented!();
+    }
+}
+
+pub fn new(_i2c: (), _sda: (), _scl: (), _config: &Config) -> crate::i2c::I2c {
+    unimplemented!();
+}
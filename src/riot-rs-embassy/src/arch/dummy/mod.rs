@@ -6,9 +6,17 @@ pub mod gpio;
 #[cfg(feature = "hwrng")]
 pub mod hwrng;
 
+#[cfg(feature = "i2c")]
+pub mod i2c;
+
+#[cfg(feature = "uart")]
+pub mod uart;
+
 #[cfg(feature = "usb")]
 pub mod usb;
 
+use crate::capabilities::Capabilities;
+
 pub use executor::{Executor, Spawner};
 
 /// Dummy type.
@@ -30,3 +38,10 @@ pub fn init() -> OptionalPeripherals {
 }
 
 pub struct SWI;
+
+/// Returns the capabilities of the MCU.
+///
+/// See the `capabilities()` function of your architecture instead.
+pub const fn capabilities() -> Capabilities {
+    Capabilities::NONE
+}
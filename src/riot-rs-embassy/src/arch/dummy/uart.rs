@@ -0,0 +1,53 @@
+use embedded_io_async::{ErrorKind, ErrorType, Read, Write};
+
+use crate::uart::{Config, ConfigError, Error};
+
+pub struct Uart;
+
+impl Uart {
+    pub(crate) fn set_baudrate(&mut self, _baudrate: u32) -> Result<(), ConfigError> {
+        unimplemented!();
+    }
+}
+
+impl ErrorType for Uart {
+    type Error = ErrorKind;
+}
+
+impl From<ErrorKind> for Error {
+    fn from(_kind: ErrorKind) -> Self {
+        Self::Other
+    }
+}
+
+impl Read for Uart {
+    async fn read(&mut self, _buf: &mut [u8]) -> Result<usize, Self::Error> {
+        unimplemented!();
+    }
+}
+
+impl Write for Uart {
+    async fn write(&mut self, _buf: &[u8]) -> Result<usize, Self::Error> {
+        unimplemented!();
+    }
+}
+
+pub fn new(
+    _uart: (),
+    _rx: (),
+    _tx: (),
+    _config: &Config,
+) -> Result<crate::uart::Uart, ConfigError> {
+    unimplemented!();
+}
+
+pub fn new_with_rtscts(
+    _uart: (),
+    _rx: (),
+    _tx: (),
+    _cts: (),
+    _rts: (),
+    _config: &Config,
+) -> Result<crate::uart::Uart, ConfigError> {
+    unimplemented!();
+}
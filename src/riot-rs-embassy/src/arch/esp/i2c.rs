@@ -0,0 +1,111 @@
+use embedded_hal_async::i2c::{self as hal_i2c, ErrorType, Operation, SevenBitAddress};
+use esp_hal::{
+    gpio::{InputPin, OutputPin},
+    i2c::{self as esp_i2c, I2C},
+    peripheral::Peripheral,
+    peripherals,
+    prelude::*,
+    Async,
+};
+
+use crate::{
+    arch,
+    i2c::{
+        transaction::{self, Transfers},
+        Config,
+    },
+};
+
+/// I2C controller based on the HAL's async [`I2C`].
+///
+/// [`transaction()`](hal_i2c::I2c::transaction) is implemented on top of the HAL's
+/// [`write_read()`](hal_i2c::I2c::write_read), whose contract requires a repeated START
+/// condition between the write and the read; adjacent operations of the same type are merged, so
+/// that no condition is emitted between them.
+pub struct I2c(I2C<'static, peripherals::I2C0, Async>);
+
+pub type Error = transaction::Error<esp_i2c::Error>;
+
+impl ErrorType for I2c {
+    type Error = Error;
+}
+
+impl Transfers for I2c {
+    type Error = esp_i2c::Error;
+
+    async fn write(&mut self, address: u8, write: &[u8]) -> Result<(), esp_i2c::Error> {
+        hal_i2c::I2c::write(&mut self.0, address, write).await
+    }
+
+    async fn read(&mut self, address: u8, read: &mut [u8]) -> Result<(), esp_i2c::Error> {
+        hal_i2c::I2c::read(&mut self.0, address, read).await
+    }
+
+    async fn write_read(
+        &mut self,
+        address: u8,
+        write: &[u8],
+        read: &mut [u8],
+    ) -> Result<(), esp_i2c::Error> {
+        hal_i2c::I2c::write_read(&mut self.0, address, write, read).await
+    }
+}
+
+impl hal_i2c::I2c<SevenBitAddress> for I2c {
+    async fn read(&mut self, address: u8, read: &mut [u8]) -> Result<(), Self::Error> {
+        Transfers::read(self, address, read)
+            .await
+            .map_err(Error::Controller)
+    }
+
+    async fn write(&mut self, address: u8, write: &[u8]) -> Result<(), Self::Error> {
+        Transfers::write(self, address, write)
+            .await
+            .map_err(Error::Controller)
+    }
+
+    async fn write_read(
+        &mut self,
+        address: u8,
+        write: &[u8],
+        read: &mut [u8],
+    ) -> Result<(), Self::Error> {
+        Transfers::write_read(self, address, write, read)
+            .await
+            .map_err(Error::Controller)
+    }
+
+    /// Executes the operations as a single transfer.
+    ///
+    /// Only writes followed by reads are supported: a transaction with a write following a read
+    /// fails with `UnsupportedTransaction`.
+    /// Adjacent operations of the same type are merged through buffers of
+    /// `CONFIG_I2C_TRANSACTION_BUF_SIZE` bytes, and fail with `TooLong` if they do not fit.
+    async fn transaction(
+        &mut self,
+        address: u8,
+        operations: &mut [Operation<'_>],
+    ) -> Result<(), Self::Error> {
+        transaction::execute(self, address, operations).await
+    }
+}
+
+/// Returns an I2C controller using the `I2C0` instance.
+pub fn new(
+    i2c: impl Peripheral<P = peripherals::I2C0> + 'static,
+    sda: impl Peripheral<P = impl OutputPin + InputPin> + 'static,
+    scl: impl Peripheral<P = impl OutputPin + InputPin> + 'static,
+    config: &Config,
+) -> crate::i2c::I2c {
+    // The HAL always enables the internal pull-ups.
+    crate::i2c::I2c::new(
+        I2c(I2C::new_async(
+            i2c,
+            sda,
+            scl,
+            config.frequency.to_hz().Hz(),
+            arch::CLOCKS.get().unwrap(),
+        )),
+        config,
+    )
+}
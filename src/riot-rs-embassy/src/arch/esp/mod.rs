@@ -1,41 +1,95 @@
 pub mod gpio;
 
-use esp_hal::{clock::ClockControl, embassy, prelude::*, timer::TimerGroup};
+#[cfg(feature = "i2c")]
+pub mod i2c;
+
+#[cfg(feature = "uart")]
+pub mod uart;
+
+use esp_hal::{
+    clock::{ClockControl, Clocks},
+    embassy,
+    prelude::*,
+    timer::TimerGroup,
+};
+use once_cell::sync::OnceCell;
+
+use crate::{
+    capabilities::{Capabilities, GpioCapabilities},
+    define_peripherals::{take, with_taker},
+};
 
 pub use esp_hal::{
     embassy::executor::Executor,
     peripherals::{OptionalPeripherals, Peripherals},
 };
 
+// Frozen clock configuration, needed to set up drivers after initialization.
+pub(crate) static CLOCKS: OnceCell<Clocks<'static>> = OnceCell::new();
+
 pub fn init() -> OptionalPeripherals {
-    let mut peripherals = OptionalPeripherals::from(Peripherals::take());
-    let system = peripherals.SYSTEM.take().unwrap().split();
-    let clocks = ClockControl::max(system.clock_control).freeze();
-
-    #[cfg(feature = "wifi-esp")]
-    {
-        use esp_hal::rng::Rng;
-        use esp_wifi::{initialize, EspWifiInitFor};
-
-        riot_rs_debug::println!("riot-rs-embassy::arch::esp::init(): wifi");
-
-        let timer = esp_hal::systimer::SystemTimer::new(peripherals.SYSTIMER.take().unwrap());
-
-        #[cfg(target_arch = "riscv32")]
-        let init = initialize(
-            EspWifiInitFor::Wifi,
-            timer.alarm0,
-            Rng::new(peripherals.RNG.take().unwrap()),
-            system.radio_clock_control,
-            &clocks,
-        )
-        .unwrap();
-
-        crate::wifi::esp_wifi::WIFI_INIT.set(init).unwrap();
-    }
+    with_taker(module_path!(), || {
+        let mut peripherals = OptionalPeripherals::from(Peripherals::take());
+        let system = take(&mut peripherals.SYSTEM, "SYSTEM").split();
+        let clocks = CLOCKS.get_or_init(|| ClockControl::max(system.clock_control).freeze());
+
+        #[cfg(feature = "wifi-esp")]
+        {
+            use esp_hal::rng::Rng;
+            use esp_wifi::{initialize, EspWifiInitFor};
+
+            riot_rs_debug::println!("riot-rs-embassy::arch::esp::init(): wifi");
 
-    let timer_group0 = TimerGroup::new_async(peripherals.TIMG0.take().unwrap(), &clocks);
-    embassy::init(&clocks, timer_group0);
+            let timer =
+                esp_hal::systimer::SystemTimer::new(take(&mut peripherals.SYSTIMER, "SYSTIMER"));
 
-    peripherals
+            #[cfg(target_arch = "riscv32")]
+            let init = initialize(
+                EspWifiInitFor::Wifi,
+                timer.alarm0,
+                Rng::new(take(&mut peripherals.RNG, "RNG")),
+                system.radio_clock_control,
+                clocks,
+            )
+            .unwrap();
+
+            crate::wifi::esp_wifi::WIFI_INIT.set(init).unwrap();
+        }
+
+        let timer_group0 = TimerGroup::new_async(take(&mut peripherals.TIMG0, "TIMG0"), clocks);
+        embassy::init(clocks, timer_group0);
+
+        peripherals
+    })
+}
+
+/// Returns the capabilities of the MCU.
+pub const fn capabilities() -> Capabilities {
+    cfg_if::cfg_if! {
+        if #[cfg(context = "esp32c3")] {
+            const PIN_COUNT: u8 = 22;
+        } else if #[cfg(context = "esp32c6")] {
+            const PIN_COUNT: u8 = 31;
+        } else {
+            compile_error!("capabilities are not defined for this ESP MCU");
+        }
+    }
+
+    Capabilities {
+        gpio: GpioCapabilities {
+            pin_count: PIN_COUNT,
+            exti_channel_count: PIN_COUNT,
+            wait_on_all_pins: true,
+            schmitt_trigger_configurable: false,
+            drive_strength_configurable: true,
+            pull_down: true,
+        },
+        i2c_bus_count: 1,
+        // Only SPI2 is available for general purpose use.
+        spi_bus_count: 1,
+        uart_count: 2,
+        max_spi_frequency: 80_000_000,
+        // Only the USB Serial/JTAG controller is available.
+        usb_device: false,
+    }
 }
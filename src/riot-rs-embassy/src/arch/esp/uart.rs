@@ -0,0 +1,165 @@
+use embedded_io_async::{ErrorType, Read, Write};
+use esp_hal::{
+    gpio::{InputPin, OutputPin},
+    peripheral::Peripheral,
+    peripherals,
+    uart::{self as esp_uart, config as esp_config, AllPins, TxRxPins, UartPins},
+    Async,
+};
+
+use crate::{
+    arch,
+    uart::{Config, ConfigError, Error, Parity, StopBits},
+};
+
+// Number of idle symbols on the line after which pending reads return.
+const RX_TIMEOUT_SYMBOLS: u8 = 10;
+
+// Baud rates that the HAL can derive from the clock, within the limits of the hardware.
+const MIN_BAUDRATE: u32 = 100;
+const MAX_BAUDRATE: u32 = 5_000_000;
+
+/// Peripheral instances usable as UARTs.
+pub trait Instance: esp_uart::Instance {
+    #[doc(hidden)]
+    fn new_uart(
+        uart: impl Peripheral<P = Self> + 'static,
+        pins: impl UartPins,
+        config: esp_config::Config,
+    ) -> Uart;
+}
+
+macro_rules! define_uart {
+    ($($instance:ident),* $(,)?) => {
+        enum Inner {
+            $($instance(esp_uart::Uart<'static, peripherals::$instance, Async>),)*
+        }
+
+        $(
+            impl Instance for peripherals::$instance {
+                fn new_uart(
+                    uart: impl Peripheral<P = Self> + 'static,
+                    pins: impl UartPins,
+                    config: esp_config::Config,
+                ) -> Uart {
+                    let mut uart = esp_uart::Uart::new_async_with_config(
+                        uart,
+                        config,
+                        Some(pins),
+                        arch::CLOCKS.get().unwrap(),
+                    );
+                    // Makes reads return once the line is idle, instead of only once the receive
+                    // FIFO is full.
+                    uart.set_rx_timeout(Some(RX_TIMEOUT_SYMBOLS)).unwrap();
+                    Uart(Inner::$instance(uart))
+                }
+            }
+        )*
+
+        impl Uart {
+            pub(crate) fn set_baudrate(&mut self, baudrate: u32) -> Result<(), ConfigError> {
+                check_baudrate(baudrate)?;
+                let clocks = arch::CLOCKS.get().unwrap();
+                match &mut self.0 {
+                    $(Inner::$instance(uart) => uart.change_baud(baudrate, clocks),)*
+                }
+                Ok(())
+            }
+        }
+
+        impl Read for Uart {
+            async fn read(&mut self, buf: &mut [u8]) -> Result<usize, Self::Error> {
+                match &mut self.0 {
+                    $(Inner::$instance(uart) => Read::read(uart, buf).await,)*
+                }
+            }
+        }
+
+        impl Write for Uart {
+            async fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
+                match &mut self.0 {
+                    $(Inner::$instance(uart) => Write::write(uart, buf).await,)*
+                }
+            }
+
+            async fn flush(&mut self) -> Result<(), Self::Error> {
+                match &mut self.0 {
+                    $(Inner::$instance(uart) => Write::flush(uart).await,)*
+                }
+            }
+        }
+    };
+}
+
+define_uart!(UART0, UART1);
+
+/// UART based on the HAL's async [`Uart`](esp_uart::Uart).
+pub struct Uart(Inner);
+
+impl ErrorType for Uart {
+    type Error = esp_uart::Error;
+}
+
+// The HAL only reports receive FIFO overflows.
+impl From<esp_uart::Error> for Error {
+    fn from(err: esp_uart::Error) -> Self {
+        match err {
+            esp_uart::Error::RxFifoOvf => Self::Overrun,
+            _ => Self::Other,
+        }
+    }
+}
+
+/// Returns a UART using the `uart` instance (`UART0` or `UART1`).
+///
+/// Fails if the configuration is not supported.
+pub fn new<T: Instance>(
+    uart: impl Peripheral<P = T> + 'static,
+    rx: impl Peripheral<P = impl InputPin> + 'static,
+    tx: impl Peripheral<P = impl OutputPin> + 'static,
+    config: &Config,
+) -> Result<crate::uart::Uart, ConfigError> {
+    let uart = T::new_uart(uart, TxRxPins::new_tx_rx(tx, rx), uart_config(config)?);
+    Ok(crate::uart::Uart::new(uart, config))
+}
+
+/// Returns a UART with hardware flow control using the `uart` instance (`UART0` or `UART1`).
+///
+/// Fails if the configuration is not supported.
+pub fn new_with_rtscts<T: Instance>(
+    uart: impl Peripheral<P = T> + 'static,
+    rx: impl Peripheral<P = impl InputPin> + 'static,
+    tx: impl Peripheral<P = impl OutputPin> + 'static,
+    cts: impl Peripheral<P = impl InputPin> + 'static,
+    rts: impl Peripheral<P = impl OutputPin> + 'static,
+    config: &Config,
+) -> Result<crate::uart::Uart, ConfigError> {
+    let uart = T::new_uart(uart, AllPins::new(tx, rx, cts, rts), uart_config(config)?);
+    Ok(crate::uart::Uart::new(uart, config))
+}
+
+fn uart_config(config: &Config) -> Result<esp_config::Config, ConfigError> {
+    check_baudrate(config.baudrate)?;
+
+    let mut uart_config = esp_config::Config::default();
+    uart_config.baudrate = config.baudrate;
+    uart_config.parity = match config.parity {
+        Parity::None => esp_config::Parity::ParityNone,
+        Parity::Even => esp_config::Parity::ParityEven,
+        Parity::Odd => esp_config::Parity::ParityOdd,
+    };
+    uart_config.stop_bits = match config.stop_bits {
+        StopBits::One => esp_config::StopBits::STOP1,
+        StopBits::Two => esp_config::StopBits::STOP2,
+    };
+    Ok(uart_config)
+}
+
+// The HAL divides by the baud rate, and does not check that the divider fits its register.
+fn check_baudrate(baudrate: u32) -> Result<(), ConfigError> {
+    if (MIN_BAUDRATE..=MAX_BAUDRATE).contains(&baudrate) {
+        Ok(())
+    } else {
+        Err(ConfigError::Baudrate)
+    }
+}
@@ -0,0 +1,156 @@
+use embassy_nrf::{
+    gpio, peripherals,
+    twim::{self, Twim},
+    Peripheral,
+};
+use embedded_hal_async::i2c::{self as hal_i2c, ErrorType, Operation, SevenBitAddress};
+
+use crate::i2c::{
+    transaction::{self, Transfers},
+    Config, Frequency,
+};
+
+#[cfg(context = "nrf52")]
+embassy_nrf::bind_interrupts!(struct Irqs {
+    SPIM0_SPIS0_TWIM0_TWIS0_SPI0_TWI0 => twim::InterruptHandler<peripherals::TWISPI0>;
+    SPIM1_SPIS1_TWIM1_TWIS1_SPI1_TWI1 => twim::InterruptHandler<peripherals::TWISPI1>;
+});
+
+#[cfg(context = "nrf5340")]
+use super::serial::{self, Irqs};
+
+/// Peripheral instances usable as I2C controllers.
+pub trait Instance: twim::Instance {
+    #[doc(hidden)]
+    fn new_i2c(
+        twim: impl Peripheral<P = Self> + 'static,
+        sda: impl Peripheral<P = impl gpio::Pin> + 'static,
+        scl: impl Peripheral<P = impl gpio::Pin> + 'static,
+        config: twim::Config,
+    ) -> I2c;
+}
+
+macro_rules! define_i2c {
+    ($($instance:ident),* $(,)?) => {
+        enum Inner {
+            $($instance(Twim<'static, peripherals::$instance>),)*
+        }
+
+        $(
+            impl Instance for peripherals::$instance {
+                fn new_i2c(
+                    twim: impl Peripheral<P = Self> + 'static,
+                    sda: impl Peripheral<P = impl gpio::Pin> + 'static,
+                    scl: impl Peripheral<P = impl gpio::Pin> + 'static,
+                    config: twim::Config,
+                ) -> I2c {
+                    #[cfg(context = "nrf5340")]
+                    serial::set_driver::<Self>(serial::Driver::Twim);
+
+                    I2c(Inner::$instance(Twim::new(twim, Irqs, sda, scl, config)))
+                }
+            }
+        )*
+
+        impl Transfers for I2c {
+            type Error = twim::Error;
+
+            async fn write(&mut self, address: u8, write: &[u8]) -> Result<(), twim::Error> {
+                match &mut self.0 {
+                    $(Inner::$instance(twim) => twim.write(address, write).await,)*
+                }
+            }
+
+            async fn read(&mut self, address: u8, read: &mut [u8]) -> Result<(), twim::Error> {
+                match &mut self.0 {
+                    $(Inner::$instance(twim) => twim.read(address, read).await,)*
+                }
+            }
+
+            async fn write_read(
+                &mut self,
+                address: u8,
+                write: &[u8],
+                read: &mut [u8],
+            ) -> Result<(), twim::Error> {
+                match &mut self.0 {
+                    $(Inner::$instance(twim) => twim.write_read(address, write, read).await,)*
+                }
+            }
+        }
+    };
+}
+
+#[cfg(context = "nrf52")]
+define_i2c!(TWISPI0, TWISPI1);
+
+#[cfg(context = "nrf5340")]
+define_i2c!(SERIAL0, SERIAL1, SERIAL2, SERIAL3);
+
+/// I2C controller based on [`Twim`], which does not implement
+/// [`transaction()`](hal_i2c::I2c::transaction) itself.
+pub struct I2c(Inner);
+
+pub type Error = transaction::Error<twim::Error>;
+
+impl ErrorType for I2c {
+    type Error = Error;
+}
+
+impl hal_i2c::I2c<SevenBitAddress> for I2c {
+    async fn read(&mut self, address: u8, read: &mut [u8]) -> Result<(), Self::Error> {
+        Transfers::read(self, address, read)
+            .await
+            .map_err(Error::Controller)
+    }
+
+    async fn write(&mut self, address: u8, write: &[u8]) -> Result<(), Self::Error> {
+        Transfers::write(self, address, write)
+            .await
+            .map_err(Error::Controller)
+    }
+
+    async fn write_read(
+        &mut self,
+        address: u8,
+        write: &[u8],
+        read: &mut [u8],
+    ) -> Result<(), Self::Error> {
+        Transfers::write_read(self, address, write, read)
+            .await
+            .map_err(Error::Controller)
+    }
+
+    /// Executes the operations as a single transfer.
+    ///
+    /// The hardware can only execute writes followed by reads: a transaction with a write
+    /// following a read fails with `UnsupportedTransaction`.
+    /// Adjacent operations of the same type are merged through buffers of
+    /// `CONFIG_I2C_TRANSACTION_BUF_SIZE` bytes, and fail with `TooLong`
+    /// if they do not fit.
+    async fn transaction(
+        &mut self,
+        address: u8,
+        operations: &mut [Operation<'_>],
+    ) -> Result<(), Self::Error> {
+        transaction::execute(self, address, operations).await
+    }
+}
+
+/// Returns an I2C controller using the `twim` instance (`TWISPIn` on nRF52, `SERIALn` on nRF5340).
+pub fn new<T: Instance>(
+    twim: impl Peripheral<P = T> + 'static,
+    sda: impl Peripheral<P = impl gpio::Pin> + 'static,
+    scl: impl Peripheral<P = impl gpio::Pin> + 'static,
+    config: &Config,
+) -> crate::i2c::I2c {
+    let mut twim_config = twim::Config::default();
+    twim_config.frequency = match config.frequency {
+        Frequency::K100 => twim::Frequency::K100,
+        Frequency::K400 => twim::Frequency::K400,
+    };
+    twim_config.sda_pullup = config.internal_pull_ups;
+    twim_config.scl_pullup = config.internal_pull_ups;
+
+    crate::i2c::I2c::new(T::new_i2c(twim, sda, scl, twim_config), config)
+}
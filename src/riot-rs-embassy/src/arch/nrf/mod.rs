@@ -3,6 +3,15 @@ pub mod gpio;
 #[cfg(feature = "hwrng")]
 pub mod hwrng;
 
+#[cfg(feature = "i2c")]
+pub mod i2c;
+
+#[cfg(all(context = "nrf5340", any(feature = "i2c", feature = "uart")))]
+mod serial;
+
+#[cfg(feature = "uart")]
+pub mod uart;
+
 #[cfg(feature = "usb")]
 pub mod usb;
 
@@ -16,9 +25,64 @@ crate::executor_swi!(EGU0);
 
 use embassy_nrf::config::Config;
 
+use crate::capabilities::{Capabilities, GpioCapabilities};
+
 pub use embassy_nrf::{interrupt, peripherals, OptionalPeripherals};
 
 pub fn init() -> OptionalPeripherals {
     let peripherals = embassy_nrf::init(Config::default());
     OptionalPeripherals::from(peripherals)
 }
+
+/// Returns the capabilities of the MCU.
+pub const fn capabilities() -> Capabilities {
+    cfg_if::cfg_if! {
+        if #[cfg(context = "nrf52832")] {
+            const PIN_COUNT: u8 = 32;
+            // TWIM and SPIM share serial bus instances.
+            const I2C_BUS_COUNT: u8 = 2;
+            const SPI_BUS_COUNT: u8 = 3;
+            const UART_COUNT: u8 = 1;
+            const MAX_SPI_FREQUENCY: u32 = 8_000_000;
+            const USB_DEVICE: bool = false;
+        } else if #[cfg(context = "nrf52840")] {
+            const PIN_COUNT: u8 = 48;
+            const I2C_BUS_COUNT: u8 = 2;
+            const SPI_BUS_COUNT: u8 = 4;
+            const UART_COUNT: u8 = 2;
+            // SPIM3 supports up to 32 MHz.
+            const MAX_SPI_FREQUENCY: u32 = 32_000_000;
+            const USB_DEVICE: bool = true;
+        } else if #[cfg(context = "nrf5340")] {
+            const PIN_COUNT: u8 = 48;
+            // TWIM, SPIM and UARTE share the SERIALn instances.
+            const I2C_BUS_COUNT: u8 = 4;
+            const SPI_BUS_COUNT: u8 = 5;
+            // The UART driver needs a timer per instance, and there are only three.
+            const UART_COUNT: u8 = 3;
+            // SPIM4 supports up to 32 MHz.
+            const MAX_SPI_FREQUENCY: u32 = 32_000_000;
+            const USB_DEVICE: bool = true;
+        } else {
+            compile_error!("capabilities are not defined for this nRF MCU");
+        }
+    }
+
+    Capabilities {
+        gpio: GpioCapabilities {
+            pin_count: PIN_COUNT,
+            // GPIOTE IN channels.
+            exti_channel_count: 8,
+            // The GPIOTE PORT event senses the level of any number of pins.
+            wait_on_all_pins: true,
+            schmitt_trigger_configurable: false,
+            drive_strength_configurable: true,
+            pull_down: true,
+        },
+        i2c_bus_count: I2C_BUS_COUNT,
+        spi_bus_count: SPI_BUS_COUNT,
+        uart_count: UART_COUNT,
+        max_spi_frequency: MAX_SPI_FREQUENCY,
+        usb_device: USB_DEVICE,
+    }
+}
@@ -0,0 +1,95 @@
+//! Dispatches the interrupts of the nRF5340 serial instances.
+//!
+//! Each `SERIALn` instance can be used as a TWIM (I2C) or a UARTE, which share a single interrupt.
+//! Drivers record which of them currently uses an instance before enabling its interrupt.
+
+use core::sync::atomic::{AtomicU8, Ordering};
+
+#[cfg(feature = "uart")]
+use embassy_nrf::buffered_uarte;
+#[cfg(feature = "i2c")]
+use embassy_nrf::twim;
+use embassy_nrf::{
+    interrupt,
+    interrupt::typelevel::{self, Binding, Handler},
+    peripherals,
+};
+
+/// Driver using a serial instance.
+#[derive(Copy, Clone, PartialEq, Eq)]
+#[repr(u8)]
+pub(crate) enum Driver {
+    None = 0,
+    #[cfg(feature = "i2c")]
+    Twim = 1,
+    #[cfg(feature = "uart")]
+    Uarte = 2,
+}
+
+const NONE: u8 = Driver::None as u8;
+#[cfg(feature = "i2c")]
+const TWIM: u8 = Driver::Twim as u8;
+#[cfg(feature = "uart")]
+const UARTE: u8 = Driver::Uarte as u8;
+
+/// Serial instances that can be shared between drivers.
+pub(crate) trait Instance {
+    fn driver() -> &'static AtomicU8;
+}
+
+/// Records that `driver` uses the serial instance `T`.
+pub(crate) fn set_driver<T: Instance>(driver: Driver) {
+    T::driver().store(driver as u8, Ordering::Release);
+}
+
+/// Binds the serial interrupts to whichever driver currently uses the instance.
+#[derive(Copy, Clone)]
+pub(crate) struct Irqs;
+
+macro_rules! dispatch_interrupts {
+    ($($instance:ident),* $(,)?) => {
+        $(
+            impl Instance for peripherals::$instance {
+                fn driver() -> &'static AtomicU8 {
+                    static DRIVER: AtomicU8 = AtomicU8::new(NONE);
+                    &DRIVER
+                }
+            }
+
+            #[interrupt]
+            unsafe fn $instance() {
+                let driver = <peripherals::$instance as Instance>::driver().load(Ordering::Acquire);
+                // SAFETY: the interrupt handler of the driver using the instance is called from
+                // the interrupt of the instance.
+                unsafe {
+                    match driver {
+                        #[cfg(feature = "i2c")]
+                        TWIM => <twim::InterruptHandler<peripherals::$instance> as Handler<
+                            typelevel::$instance,
+                        >>::on_interrupt(),
+                        #[cfg(feature = "uart")]
+                        UARTE => <buffered_uarte::InterruptHandler<peripherals::$instance> as Handler<
+                            typelevel::$instance,
+                        >>::on_interrupt(),
+                        _ => {}
+                    }
+                }
+            }
+
+            #[cfg(feature = "i2c")]
+            unsafe impl Binding<typelevel::$instance, twim::InterruptHandler<peripherals::$instance>>
+                for Irqs
+            {
+            }
+
+            #[cfg(feature = "uart")]
+            unsafe impl
+                Binding<typelevel::$instance, buffered_uarte::InterruptHandler<peripherals::$instance>>
+                for Irqs
+            {
+            }
+        )*
+    };
+}
+
+dispatch_interrupts!(SERIAL0, SERIAL1, SERIAL2, SERIAL3);
@@ -0,0 +1,318 @@
+use embassy_nrf::{
+    buffered_uarte::{self, BufferedUarte},
+    gpio, peripherals,
+    ppi::{ConfigurableChannel, Group},
+    timer, uarte, Peripheral,
+};
+use embedded_io_async::{ErrorType, Read, Write};
+
+use crate::{
+    make_static,
+    uart::{Config, ConfigError, Error, Parity, StopBits, RX_BUF_SIZE, TX_BUF_SIZE},
+};
+
+#[cfg(context = "nrf52832")]
+embassy_nrf::bind_interrupts!(struct Irqs {
+    UARTE0_UART0 => buffered_uarte::InterruptHandler<peripherals::UARTE0>;
+});
+
+#[cfg(context = "nrf52840")]
+embassy_nrf::bind_interrupts!(struct Irqs {
+    UARTE0_UART0 => buffered_uarte::InterruptHandler<peripherals::UARTE0>;
+    UARTE1 => buffered_uarte::InterruptHandler<peripherals::UARTE1>;
+});
+
+#[cfg(context = "nrf5340")]
+use super::serial::{self, Irqs};
+
+// EasyDMA transfers are limited to 255 bytes on nRF52832, whose MAXCNT registers are 8-bit wide.
+// The driver transmits the readable part of the transmit buffer in a single transfer, and
+// receives into halves of the receive buffer.
+#[cfg(context = "nrf52832")]
+const EASY_DMA_MAX_LEN: usize = 255;
+#[cfg(not(context = "nrf52832"))]
+const EASY_DMA_MAX_LEN: usize = 65535;
+
+const UARTE_RX_BUF_SIZE: usize = min(RX_BUF_SIZE, 2 * EASY_DMA_MAX_LEN);
+const UARTE_TX_BUF_SIZE: usize = min(TX_BUF_SIZE, EASY_DMA_MAX_LEN);
+
+const fn min(a: usize, b: usize) -> usize {
+    if a < b {
+        a
+    } else {
+        b
+    }
+}
+
+/// Peripheral instances usable as UARTs.
+pub trait Instance: uarte::Instance {
+    /// Timer that the driver needs alongside this instance to count received bytes.
+    ///
+    /// This is `TIMER1` for `UARTE0` and `TIMER2` for `UARTE1` on nRF52, and `TIMERn` for
+    /// `SERIALn` on nRF5340, where `SERIAL3` has no timer and cannot be used.
+    type Timer: timer::Instance;
+
+    #[doc(hidden)]
+    #[allow(clippy::too_many_arguments)]
+    fn new_uart(
+        uarte: impl Peripheral<P = Self> + 'static,
+        timer: impl Peripheral<P = Self::Timer> + 'static,
+        ppi_ch1: impl Peripheral<P = impl ConfigurableChannel> + 'static,
+        ppi_ch2: impl Peripheral<P = impl ConfigurableChannel> + 'static,
+        ppi_group: impl Peripheral<P = impl Group> + 'static,
+        rx: impl Peripheral<P = impl gpio::Pin> + 'static,
+        tx: impl Peripheral<P = impl gpio::Pin> + 'static,
+        config: uarte::Config,
+    ) -> Uart;
+
+    #[doc(hidden)]
+    #[allow(clippy::too_many_arguments)]
+    fn new_uart_with_rtscts(
+        uarte: impl Peripheral<P = Self> + 'static,
+        timer: impl Peripheral<P = Self::Timer> + 'static,
+        ppi_ch1: impl Peripheral<P = impl ConfigurableChannel> + 'static,
+        ppi_ch2: impl Peripheral<P = impl ConfigurableChannel> + 'static,
+        ppi_group: impl Peripheral<P = impl Group> + 'static,
+        rx: impl Peripheral<P = impl gpio::Pin> + 'static,
+        tx: impl Peripheral<P = impl gpio::Pin> + 'static,
+        cts: impl Peripheral<P = impl gpio::Pin> + 'static,
+        rts: impl Peripheral<P = impl gpio::Pin> + 'static,
+        config: uarte::Config,
+    ) -> Uart;
+}
+
+macro_rules! define_uart {
+    ($($instance:ident => $timer:ident),* $(,)?) => {
+        enum Inner {
+            $($instance(BufferedUarte<'static, peripherals::$instance, peripherals::$timer>),)*
+        }
+
+        $(
+            impl Instance for peripherals::$instance {
+                type Timer = peripherals::$timer;
+
+                fn new_uart(
+                    uarte: impl Peripheral<P = Self> + 'static,
+                    timer: impl Peripheral<P = Self::Timer> + 'static,
+                    ppi_ch1: impl Peripheral<P = impl ConfigurableChannel> + 'static,
+                    ppi_ch2: impl Peripheral<P = impl ConfigurableChannel> + 'static,
+                    ppi_group: impl Peripheral<P = impl Group> + 'static,
+                    rx: impl Peripheral<P = impl gpio::Pin> + 'static,
+                    tx: impl Peripheral<P = impl gpio::Pin> + 'static,
+                    config: uarte::Config,
+                ) -> Uart {
+                    #[cfg(context = "nrf5340")]
+                    serial::set_driver::<Self>(serial::Driver::Uarte);
+
+                    Uart(Inner::$instance(BufferedUarte::new(
+                        uarte,
+                        timer,
+                        ppi_ch1,
+                        ppi_ch2,
+                        ppi_group,
+                        Irqs,
+                        rx,
+                        tx,
+                        config,
+                        make_static!([0; UARTE_RX_BUF_SIZE]),
+                        make_static!([0; UARTE_TX_BUF_SIZE]),
+                    )))
+                }
+
+                fn new_uart_with_rtscts(
+                    uarte: impl Peripheral<P = Self> + 'static,
+                    timer: impl Peripheral<P = Self::Timer> + 'static,
+                    ppi_ch1: impl Peripheral<P = impl ConfigurableChannel> + 'static,
+                    ppi_ch2: impl Peripheral<P = impl ConfigurableChannel> + 'static,
+                    ppi_group: impl Peripheral<P = impl Group> + 'static,
+                    rx: impl Peripheral<P = impl gpio::Pin> + 'static,
+                    tx: impl Peripheral<P = impl gpio::Pin> + 'static,
+                    cts: impl Peripheral<P = impl gpio::Pin> + 'static,
+                    rts: impl Peripheral<P = impl gpio::Pin> + 'static,
+                    config: uarte::Config,
+                ) -> Uart {
+                    #[cfg(context = "nrf5340")]
+                    serial::set_driver::<Self>(serial::Driver::Uarte);
+
+                    Uart(Inner::$instance(BufferedUarte::new_with_rtscts(
+                        uarte,
+                        timer,
+                        ppi_ch1,
+                        ppi_ch2,
+                        ppi_group,
+                        Irqs,
+                        rx,
+                        tx,
+                        cts,
+                        rts,
+                        config,
+                        make_static!([0; UARTE_RX_BUF_SIZE]),
+                        make_static!([0; UARTE_TX_BUF_SIZE]),
+                    )))
+                }
+            }
+        )*
+
+        impl Uart {
+            pub(crate) fn set_baudrate(&mut self, baudrate: u32) -> Result<(), ConfigError> {
+                let baudrate = uarte_baudrate(baudrate)?;
+                match &mut self.0 {
+                    $(Inner::$instance(uarte) => uarte.set_baudrate(baudrate),)*
+                }
+                Ok(())
+            }
+        }
+
+        impl Read for Uart {
+            async fn read(&mut self, buf: &mut [u8]) -> Result<usize, Self::Error> {
+                match &mut self.0 {
+                    $(Inner::$instance(uarte) => uarte.read(buf).await,)*
+                }
+            }
+        }
+
+        impl Write for Uart {
+            async fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
+                match &mut self.0 {
+                    $(Inner::$instance(uarte) => uarte.write(buf).await,)*
+                }
+            }
+
+            async fn flush(&mut self) -> Result<(), Self::Error> {
+                match &mut self.0 {
+                    $(Inner::$instance(uarte) => uarte.flush().await,)*
+                }
+            }
+        }
+    };
+}
+
+#[cfg(context = "nrf52832")]
+define_uart!(UARTE0 => TIMER1);
+
+#[cfg(context = "nrf52840")]
+define_uart!(UARTE0 => TIMER1, UARTE1 => TIMER2);
+
+#[cfg(context = "nrf5340")]
+define_uart!(SERIAL0 => TIMER0, SERIAL1 => TIMER1, SERIAL2 => TIMER2);
+
+/// UART based on [`BufferedUarte`].
+pub struct Uart(Inner);
+
+impl ErrorType for Uart {
+    type Error = buffered_uarte::Error;
+}
+
+// The driver does not report any error: it panics on receive overruns, and ignores other line
+// errors.
+impl From<buffered_uarte::Error> for Error {
+    fn from(_err: buffered_uarte::Error) -> Self {
+        Self::Other
+    }
+}
+
+/// Returns a UART using the `uarte` instance.
+///
+/// This also takes the [timer](Instance::Timer) associated with the instance, two PPI channels
+/// and a PPI group, which the driver needs to count received bytes.
+///
+/// Fails if the configuration is not supported.
+#[allow(clippy::too_many_arguments)]
+pub fn new<T: Instance>(
+    uarte: impl Peripheral<P = T> + 'static,
+    timer: impl Peripheral<P = T::Timer> + 'static,
+    ppi_ch1: impl Peripheral<P = impl ConfigurableChannel> + 'static,
+    ppi_ch2: impl Peripheral<P = impl ConfigurableChannel> + 'static,
+    ppi_group: impl Peripheral<P = impl Group> + 'static,
+    rx: impl Peripheral<P = impl gpio::Pin> + 'static,
+    tx: impl Peripheral<P = impl gpio::Pin> + 'static,
+    config: &Config,
+) -> Result<crate::uart::Uart, ConfigError> {
+    let uarte_config = uarte_config(config)?;
+    let uart = T::new_uart(
+        uarte,
+        timer,
+        ppi_ch1,
+        ppi_ch2,
+        ppi_group,
+        rx,
+        tx,
+        uarte_config,
+    );
+    Ok(crate::uart::Uart::new(uart, config))
+}
+
+/// Returns a UART with hardware flow control using the `uarte` instance.
+///
+/// This also takes the [timer](Instance::Timer) associated with the instance, two PPI channels
+/// and a PPI group, which the driver needs to count received bytes.
+///
+/// Fails if the configuration is not supported.
+#[allow(clippy::too_many_arguments)]
+pub fn new_with_rtscts<T: Instance>(
+    uarte: impl Peripheral<P = T> + 'static,
+    timer: impl Peripheral<P = T::Timer> + 'static,
+    ppi_ch1: impl Peripheral<P = impl ConfigurableChannel> + 'static,
+    ppi_ch2: impl Peripheral<P = impl ConfigurableChannel> + 'static,
+    ppi_group: impl Peripheral<P = impl Group> + 'static,
+    rx: impl Peripheral<P = impl gpio::Pin> + 'static,
+    tx: impl Peripheral<P = impl gpio::Pin> + 'static,
+    cts: impl Peripheral<P = impl gpio::Pin> + 'static,
+    rts: impl Peripheral<P = impl gpio::Pin> + 'static,
+    config: &Config,
+) -> Result<crate::uart::Uart, ConfigError> {
+    let uarte_config = uarte_config(config)?;
+    let uart = T::new_uart_with_rtscts(
+        uarte,
+        timer,
+        ppi_ch1,
+        ppi_ch2,
+        ppi_group,
+        rx,
+        tx,
+        cts,
+        rts,
+        uarte_config,
+    );
+    Ok(crate::uart::Uart::new(uart, config))
+}
+
+fn uarte_config(config: &Config) -> Result<uarte::Config, ConfigError> {
+    if config.stop_bits != StopBits::One {
+        return Err(ConfigError::StopBits);
+    }
+
+    let mut uarte_config = uarte::Config::default();
+    uarte_config.parity = match config.parity {
+        Parity::None => uarte::Parity::EXCLUDED,
+        Parity::Even => uarte::Parity::INCLUDED,
+        Parity::Odd => return Err(ConfigError::Parity),
+    };
+    uarte_config.baudrate = uarte_baudrate(config.baudrate)?;
+    Ok(uarte_config)
+}
+
+fn uarte_baudrate(baudrate: u32) -> Result<uarte::Baudrate, ConfigError> {
+    let baudrate = match baudrate {
+        1200 => uarte::Baudrate::BAUD1200,
+        2400 => uarte::Baudrate::BAUD2400,
+        4800 => uarte::Baudrate::BAUD4800,
+        9600 => uarte::Baudrate::BAUD9600,
+        14400 => uarte::Baudrate::BAUD14400,
+        19200 => uarte::Baudrate::BAUD19200,
+        28800 => uarte::Baudrate::BAUD28800,
+        31250 => uarte::Baudrate::BAUD31250,
+        38400 => uarte::Baudrate::BAUD38400,
+        56000 => uarte::Baudrate::BAUD56000,
+        57600 => uarte::Baudrate::BAUD57600,
+        76800 => uarte::Baudrate::BAUD76800,
+        115_200 => uarte::Baudrate::BAUD115200,
+        230_400 => uarte::Baudrate::BAUD230400,
+        250_000 => uarte::Baudrate::BAUD250000,
+        460_800 => uarte::Baudrate::BAUD460800,
+        921_600 => uarte::Baudrate::BAUD921600,
+        1_000_000 => uarte::Baudrate::BAUD1M,
+        _ => return Err(ConfigError::Baudrate),
+    };
+    Ok(baudrate)
+}
@@ -7,7 +7,10 @@ use embassy_nrf::{
     },
 };
 
-use crate::arch;
+use crate::{
+    arch,
+    define_peripherals::{take, with_taker},
+};
 
 #[cfg(context = "nrf52")]
 bind_interrupts!(struct Irqs {
@@ -24,6 +27,6 @@ bind_interrupts!(struct Irqs {
 pub type UsbDriver = Driver<'static, peripherals::USBD, HardwareVbusDetect>;
 
 pub fn driver(peripherals: &mut arch::OptionalPeripherals) -> UsbDriver {
-    let usbd = peripherals.USBD.take().unwrap();
+    let usbd = with_taker(module_path!(), || take(&mut peripherals.USBD, "USBD"));
     Driver::new(usbd, Irqs, HardwareVbusDetect::new(Irqs))
 }
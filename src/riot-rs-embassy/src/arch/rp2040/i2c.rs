@@ -0,0 +1,120 @@
+use embassy_rp::{
+    bind_interrupts,
+    i2c::{self as rp_i2c, InterruptHandler, SclPin, SdaPin},
+    peripherals, Peripheral,
+};
+use embedded_hal_async::i2c::{self as hal_i2c, ErrorType, Operation, SevenBitAddress};
+
+use crate::i2c::Config;
+
+bind_interrupts!(struct Irqs {
+    I2C0_IRQ => InterruptHandler<peripherals::I2C0>;
+    I2C1_IRQ => InterruptHandler<peripherals::I2C1>;
+});
+
+/// Peripheral instances usable as I2C controllers.
+pub trait Instance: rp_i2c::Instance {
+    #[doc(hidden)]
+    fn new_i2c(
+        i2c: impl Peripheral<P = Self> + 'static,
+        sda: impl Peripheral<P = impl SdaPin<Self>> + 'static,
+        scl: impl Peripheral<P = impl SclPin<Self>> + 'static,
+        config: rp_i2c::Config,
+    ) -> I2c;
+}
+
+macro_rules! define_i2c {
+    ($($instance:ident),* $(,)?) => {
+        enum Inner {
+            $($instance(rp_i2c::I2c<'static, peripherals::$instance, rp_i2c::Async>),)*
+        }
+
+        $(
+            impl Instance for peripherals::$instance {
+                fn new_i2c(
+                    i2c: impl Peripheral<P = Self> + 'static,
+                    sda: impl Peripheral<P = impl SdaPin<Self>> + 'static,
+                    scl: impl Peripheral<P = impl SclPin<Self>> + 'static,
+                    config: rp_i2c::Config,
+                ) -> I2c {
+                    I2c(Inner::$instance(rp_i2c::I2c::new_async(i2c, scl, sda, Irqs, config)))
+                }
+            }
+        )*
+
+        // The HAL implements transactions with repeated START conditions.
+        impl hal_i2c::I2c<SevenBitAddress> for I2c {
+            async fn read(&mut self, address: u8, read: &mut [u8]) -> Result<(), Self::Error> {
+                match &mut self.0 {
+                    $(Inner::$instance(i2c) => {
+                        hal_i2c::I2c::read(i2c, address, read).await
+                    })*
+                }
+            }
+
+            async fn write(&mut self, address: u8, write: &[u8]) -> Result<(), Self::Error> {
+                match &mut self.0 {
+                    $(Inner::$instance(i2c) => {
+                        hal_i2c::I2c::write(i2c, address, write).await
+                    })*
+                }
+            }
+
+            async fn write_read(
+                &mut self,
+                address: u8,
+                write: &[u8],
+                read: &mut [u8],
+            ) -> Result<(), Self::Error> {
+                match &mut self.0 {
+                    $(Inner::$instance(i2c) => {
+                        hal_i2c::I2c::write_read(i2c, address, write, read).await
+                    })*
+                }
+            }
+
+            async fn transaction(
+                &mut self,
+                address: u8,
+                operations: &mut [Operation<'_>],
+            ) -> Result<(), Self::Error> {
+                match &mut self.0 {
+                    $(Inner::$instance(i2c) => {
+                        hal_i2c::I2c::transaction(i2c, address, operations).await
+                    })*
+                }
+            }
+        }
+    };
+}
+
+define_i2c!(I2C0, I2C1);
+
+/// I2C controller based on the HAL's async [`I2c`](rp_i2c::I2c).
+pub struct I2c(Inner);
+
+pub type Error = rp_i2c::Error;
+
+impl ErrorType for I2c {
+    type Error = Error;
+}
+
+impl From<Error> for crate::i2c::Error {
+    fn from(err: Error) -> Self {
+        Self::Bus(hal_i2c::Error::kind(&err))
+    }
+}
+
+/// Returns an I2C controller using the `i2c` instance (`I2C0` or `I2C1`).
+pub fn new<T: Instance>(
+    i2c: impl Peripheral<P = T> + 'static,
+    sda: impl Peripheral<P = impl SdaPin<T>> + 'static,
+    scl: impl Peripheral<P = impl SclPin<T>> + 'static,
+    config: &Config,
+) -> crate::i2c::I2c {
+    // The HAL always enables the internal pull-ups.
+    let mut i2c_config = rp_i2c::Config::default();
+    i2c_config.frequency = config.frequency.to_hz();
+
+    crate::i2c::I2c::new(T::new_i2c(i2c, sda, scl, i2c_config), config)
+}
@@ -1,10 +1,18 @@
 pub mod gpio;
 
+#[cfg(feature = "i2c")]
+pub mod i2c;
+
+#[cfg(feature = "uart")]
+pub mod uart;
+
 #[cfg(feature = "usb")]
 pub mod usb;
 
 use embassy_rp::config::Config;
 
+use crate::capabilities::{Capabilities, GpioCapabilities};
+
 pub(crate) use embassy_executor::InterruptExecutor as Executor;
 pub use embassy_rp::interrupt;
 pub use embassy_rp::{peripherals, OptionalPeripherals};
@@ -19,3 +27,23 @@ pub fn init() -> OptionalPeripherals {
     let peripherals = embassy_rp::init(Config::default());
     OptionalPeripherals::from(peripherals)
 }
+
+/// Returns the capabilities of the MCU.
+pub const fn capabilities() -> Capabilities {
+    Capabilities {
+        gpio: GpioCapabilities {
+            pin_count: 30,
+            exti_channel_count: 30,
+            wait_on_all_pins: true,
+            schmitt_trigger_configurable: true,
+            drive_strength_configurable: true,
+            pull_down: true,
+        },
+        i2c_bus_count: 2,
+        spi_bus_count: 2,
+        uart_count: 2,
+        // Half of `clk_peri` at its default frequency of 125 MHz.
+        max_spi_frequency: 62_500_000,
+        usb_device: true,
+    }
+}
@@ -0,0 +1,198 @@
+use embassy_rp::{
+    bind_interrupts, clocks, peripherals,
+    uart::{self as rp_uart, BufferedInterruptHandler, BufferedUart, CtsPin, RtsPin, RxPin, TxPin},
+    Peripheral,
+};
+use embedded_io_async::{ErrorType, Read, Write};
+
+use crate::{
+    make_static,
+    uart::{Config, ConfigError, Error, Parity, StopBits, RX_BUF_SIZE, TX_BUF_SIZE},
+};
+
+bind_interrupts!(struct Irqs {
+    UART0_IRQ => BufferedInterruptHandler<peripherals::UART0>;
+    UART1_IRQ => BufferedInterruptHandler<peripherals::UART1>;
+});
+
+/// Peripheral instances usable as UARTs.
+pub trait Instance: rp_uart::Instance {
+    #[doc(hidden)]
+    fn new_uart(
+        uart: impl Peripheral<P = Self> + 'static,
+        rx: impl Peripheral<P = impl RxPin<Self>> + 'static,
+        tx: impl Peripheral<P = impl TxPin<Self>> + 'static,
+        config: rp_uart::Config,
+    ) -> Uart;
+
+    #[doc(hidden)]
+    fn new_uart_with_rtscts(
+        uart: impl Peripheral<P = Self> + 'static,
+        rx: impl Peripheral<P = impl RxPin<Self>> + 'static,
+        tx: impl Peripheral<P = impl TxPin<Self>> + 'static,
+        cts: impl Peripheral<P = impl CtsPin<Self>> + 'static,
+        rts: impl Peripheral<P = impl RtsPin<Self>> + 'static,
+        config: rp_uart::Config,
+    ) -> Uart;
+}
+
+macro_rules! define_uart {
+    ($($instance:ident),* $(,)?) => {
+        enum Inner {
+            $($instance(BufferedUart<'static, peripherals::$instance>),)*
+        }
+
+        $(
+            impl Instance for peripherals::$instance {
+                fn new_uart(
+                    uart: impl Peripheral<P = Self> + 'static,
+                    rx: impl Peripheral<P = impl RxPin<Self>> + 'static,
+                    tx: impl Peripheral<P = impl TxPin<Self>> + 'static,
+                    config: rp_uart::Config,
+                ) -> Uart {
+                    Uart(Inner::$instance(BufferedUart::new(
+                        uart,
+                        Irqs,
+                        tx,
+                        rx,
+                        make_static!([0; TX_BUF_SIZE]),
+                        make_static!([0; RX_BUF_SIZE]),
+                        config,
+                    )))
+                }
+
+                fn new_uart_with_rtscts(
+                    uart: impl Peripheral<P = Self> + 'static,
+                    rx: impl Peripheral<P = impl RxPin<Self>> + 'static,
+                    tx: impl Peripheral<P = impl TxPin<Self>> + 'static,
+                    cts: impl Peripheral<P = impl CtsPin<Self>> + 'static,
+                    rts: impl Peripheral<P = impl RtsPin<Self>> + 'static,
+                    config: rp_uart::Config,
+                ) -> Uart {
+                    Uart(Inner::$instance(BufferedUart::new_with_rtscts(
+                        uart,
+                        Irqs,
+                        tx,
+                        rx,
+                        rts,
+                        cts,
+                        make_static!([0; TX_BUF_SIZE]),
+                        make_static!([0; RX_BUF_SIZE]),
+                        config,
+                    )))
+                }
+            }
+        )*
+
+        impl Uart {
+            pub(crate) fn set_baudrate(&mut self, baudrate: u32) -> Result<(), ConfigError> {
+                check_baudrate(baudrate)?;
+                match &mut self.0 {
+                    $(Inner::$instance(uart) => uart.set_baudrate(baudrate),)*
+                }
+                Ok(())
+            }
+        }
+
+        impl Read for Uart {
+            async fn read(&mut self, buf: &mut [u8]) -> Result<usize, Self::Error> {
+                match &mut self.0 {
+                    $(Inner::$instance(uart) => uart.read(buf).await,)*
+                }
+            }
+        }
+
+        impl Write for Uart {
+            async fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
+                match &mut self.0 {
+                    $(Inner::$instance(uart) => uart.write(buf).await,)*
+                }
+            }
+
+            async fn flush(&mut self) -> Result<(), Self::Error> {
+                match &mut self.0 {
+                    $(Inner::$instance(uart) => uart.flush().await,)*
+                }
+            }
+        }
+    };
+}
+
+define_uart!(UART0, UART1);
+
+/// UART based on [`BufferedUart`].
+pub struct Uart(Inner);
+
+impl ErrorType for Uart {
+    type Error = rp_uart::Error;
+}
+
+impl From<rp_uart::Error> for Error {
+    fn from(err: rp_uart::Error) -> Self {
+        match err {
+            rp_uart::Error::Overrun => Self::Overrun,
+            rp_uart::Error::Break => Self::Break,
+            rp_uart::Error::Parity => Self::Parity,
+            rp_uart::Error::Framing => Self::Framing,
+            _ => Self::Other,
+        }
+    }
+}
+
+/// Returns a UART using the `uart` instance (`UART0` or `UART1`).
+///
+/// Fails if the configuration is not supported.
+pub fn new<T: Instance>(
+    uart: impl Peripheral<P = T> + 'static,
+    rx: impl Peripheral<P = impl RxPin<T>> + 'static,
+    tx: impl Peripheral<P = impl TxPin<T>> + 'static,
+    config: &Config,
+) -> Result<crate::uart::Uart, ConfigError> {
+    let uart = T::new_uart(uart, rx, tx, uart_config(config)?);
+    Ok(crate::uart::Uart::new(uart, config))
+}
+
+/// Returns a UART with hardware flow control using the `uart` instance (`UART0` or `UART1`).
+///
+/// Fails if the configuration is not supported.
+pub fn new_with_rtscts<T: Instance>(
+    uart: impl Peripheral<P = T> + 'static,
+    rx: impl Peripheral<P = impl RxPin<T>> + 'static,
+    tx: impl Peripheral<P = impl TxPin<T>> + 'static,
+    cts: impl Peripheral<P = impl CtsPin<T>> + 'static,
+    rts: impl Peripheral<P = impl RtsPin<T>> + 'static,
+    config: &Config,
+) -> Result<crate::uart::Uart, ConfigError> {
+    let uart = T::new_uart_with_rtscts(uart, rx, tx, cts, rts, uart_config(config)?);
+    Ok(crate::uart::Uart::new(uart, config))
+}
+
+fn uart_config(config: &Config) -> Result<rp_uart::Config, ConfigError> {
+    check_baudrate(config.baudrate)?;
+
+    let mut uart_config = rp_uart::Config::default();
+    uart_config.baudrate = config.baudrate;
+    uart_config.parity = match config.parity {
+        Parity::None => rp_uart::Parity::ParityNone,
+        Parity::Even => rp_uart::Parity::ParityEven,
+        Parity::Odd => rp_uart::Parity::ParityOdd,
+    };
+    uart_config.stop_bits = match config.stop_bits {
+        StopBits::One => rp_uart::StopBits::STOP1,
+        StopBits::Two => rp_uart::StopBits::STOP2,
+    };
+    Ok(uart_config)
+}
+
+// The integer part of the baud rate divisor, `clk_peri / (16 * baudrate)`, must be between 1 and
+// 65535, otherwise the HAL silently clamps it (and divides by zero for a zero baud rate).
+fn check_baudrate(baudrate: u32) -> Result<(), ConfigError> {
+    let clk_peri = clocks::clk_peri_freq();
+    let max = clk_peri / 16;
+    let min = clk_peri / (16 * 65535) + 1;
+    if (min..=max).contains(&baudrate) {
+        Ok(())
+    } else {
+        Err(ConfigError::Baudrate)
+    }
+}
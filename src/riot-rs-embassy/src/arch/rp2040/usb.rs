@@ -3,7 +3,10 @@ use embassy_rp::{
     usb::{Driver, InterruptHandler},
 };
 
-use crate::arch;
+use crate::{
+    arch,
+    define_peripherals::{take, with_taker},
+};
 
 bind_interrupts!(struct Irqs {
     USBCTRL_IRQ => InterruptHandler<peripherals::USB>;
@@ -12,6 +15,6 @@ bind_interrupts!(struct Irqs {
 pub type UsbDriver = Driver<'static, peripherals::USB>;
 
 pub fn driver(peripherals: &mut arch::OptionalPeripherals) -> UsbDriver {
-    let usb = peripherals.USB.take().unwrap();
+    let usb = with_taker(module_path!(), || take(&mut peripherals.USB, "USB"));
     Driver::new(usb, Irqs)
 }
@@ -1,6 +1,29 @@
+//! Bridges between threads and async code.
+//!
+//! - [`block_on()`] lets a thread block on a future, e.g., to use an async driver from a thread.
+//! - [`ThreadEvent`] lets an async task await an event signaled by a thread.
+//!
+//! # Latency
+//!
+//! Both directions only rely on the respective wakeup mechanism, and never busy-loop:
+//!
+//! - A thread blocked in [`block_on()`] sleeps until the future's waker is woken, which sets a
+//!   thread flag of that thread.
+//!   It then runs as soon as it is the highest-priority runnable thread, polls the future once,
+//!   and goes back to sleep if the future is still pending.
+//! - A task awaiting a [`ThreadEvent`] is woken when the thread signals it, and runs as soon as
+//!   its executor gets to poll it.
+//!   Signaling never blocks the thread.
+//!
+//! As the future is polled from the blocked thread, it runs at that thread's priority.
+//! However, there is no priority inheritance between threads and executors: if the future
+//! depends on work done by a lower-priority executor or thread, the blocked thread waits for as
+//! long as that work gets preempted by other threads, and the delay is not bounded.
 use core::future::Future;
 use core::pin::Pin;
 use core::task::{Context, Poll, RawWaker, RawWakerVTable, Waker};
+
+use embassy_sync::{blocking_mutex::raw::CriticalSectionRawMutex, signal::Signal};
 use riot_rs_threads::{current_pid, flags, flags::ThreadFlags, ThreadId};
 
 const THREAD_FLAG_WAKER: ThreadFlags = 1; // TODO: find more appropriate value
@@ -34,3 +57,109 @@ pub fn block_on<F: Future>(mut fut: F) -> F::Output {
         flags::wait_any(THREAD_FLAG_WAKER);
     }
 }
+
+/// An event signaled by a thread (or an ISR), to be awaited by an async task.
+///
+/// If the event is signaled multiple times before being awaited, only the last value is kept.
+///
+/// Example:
+/// ```Rust
+/// static SAMPLE_READY: ThreadEvent<u32> = ThreadEvent::new();
+///
+/// // in some thread
+/// fn sampler() {
+///     SAMPLE_READY.signal(42);
+/// }
+///
+/// // in some task
+/// async fn consumer() {
+///     let sample = SAMPLE_READY.wait().await;
+/// }
+/// ```
+pub struct ThreadEvent<T> {
+    signal: Signal<CriticalSectionRawMutex, T>,
+}
+
+impl<T> ThreadEvent<T> {
+    /// Creates a new [`ThreadEvent`].
+    pub const fn new() -> Self {
+        Self {
+            signal: Signal::new(),
+        }
+    }
+
+    /// Signals the event with `value`, waking up the task awaiting it.
+    ///
+    /// This never blocks, and can be called from threads, ISRs and tasks alike.
+    pub fn signal(&self, value: T) {
+        self.signal.signal(value);
+    }
+
+    /// Waits for the event to be signaled, and returns the value it was signaled with.
+    pub async fn wait(&self) -> T {
+        self.signal.wait().await
+    }
+
+    /// Returns the value the event was signaled with, if any, without waiting.
+    pub fn try_take(&self) -> Option<T> {
+        self.signal.try_take()
+    }
+}
+
+impl<T> Default for ThreadEvent<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use core::{
+        pin::pin,
+        sync::atomic::{AtomicUsize, Ordering},
+    };
+    use std::{sync::Arc, task::Wake};
+
+    use super::*;
+
+    /// Counts how many times it was woken.
+    #[derive(Default)]
+    struct CountingWaker {
+        wakes: AtomicUsize,
+    }
+
+    impl Wake for CountingWaker {
+        fn wake(self: Arc<Self>) {
+            self.wakes.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    #[test]
+    fn test_thread_event_wakes_up_waiter() {
+        let event = ThreadEvent::new();
+        let counting_waker = Arc::new(CountingWaker::default());
+        let waker = Waker::from(counting_waker.clone());
+        let mut cx = Context::from_waker(&waker);
+
+        let mut wait = pin!(event.wait());
+        assert_eq!(wait.as_mut().poll(&mut cx), Poll::Pending);
+        assert_eq!(counting_waker.wakes.load(Ordering::Relaxed), 0);
+
+        // Signaling wakes up the waiting task, and only the last value is kept.
+        event.signal(1);
+        event.signal(2);
+        assert!(counting_waker.wakes.load(Ordering::Relaxed) > 0);
+        assert_eq!(wait.as_mut().poll(&mut cx), Poll::Ready(2));
+        assert_eq!(event.try_take(), None);
+    }
+
+    #[test]
+    fn test_thread_event_signaled_before_waiting() {
+        let event = ThreadEvent::new();
+        event.signal(42);
+
+        assert_eq!(embassy_futures::block_on(event.wait()), 42);
+    }
+}
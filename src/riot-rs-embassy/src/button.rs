@@ -0,0 +1,445 @@
+//! Provides gesture recognition for push buttons.
+//!
+//! A [`Button`] wraps an input pin implementing [`embedded_hal_async::digital::Wait`] and
+//! [`embedded_hal::digital::InputPin`], and turns its (debounced) edges into [`Event`]s: clicks,
+//! double-clicks, long presses, and holds.
+//!
+//! Example:
+//! ```Rust
+//! let pin = Input::new(peripherals.btn1, Pull::Up);
+//! let mut button = Button::new(pin, button::Config::default());
+//!
+//! loop {
+//!     match button.next_event().await {
+//!         Ok(button::Event::Click) => println!("click"),
+//!         Ok(button::Event::Hold) => factory_reset(),
+//!         _ => {}
+//!     }
+//! }
+//! ```
+
+use embassy_time::{with_timeout, Duration, Instant, Timer};
+use embedded_hal::digital::InputPin;
+use embedded_hal_async::digital::Wait;
+
+/// Gesture recognized on a [`Button`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Event {
+    /// The button was pressed and released once.
+    Click,
+    /// The button was pressed twice in a row.
+    ///
+    /// Returned as soon as the button is pressed for the second time.
+    DoubleClick,
+    /// The button has been kept pressed for [`Config::long_press`].
+    ///
+    /// Returned while the button is still pressed.
+    LongPress,
+    /// The button has been kept pressed for [`Config::hold`], after a [`Event::LongPress`].
+    ///
+    /// Returned while the button is still pressed.
+    Hold,
+}
+
+/// Button configuration.
+#[derive(Copy, Clone, Debug)]
+pub struct Config {
+    /// Whether the button is pressed when the pin is low (e.g., with a pull-up resistor).
+    pub active_low: bool,
+    /// Time for which the pin level must be stable after an edge for the edge to be taken into
+    /// account.
+    pub debounce: Duration,
+    /// Maximum time between the release and the second press of a double-click.
+    ///
+    /// Single clicks are only returned once this delay has elapsed.
+    pub double_click: Duration,
+    /// Press duration after which a long press is recognized.
+    pub long_press: Duration,
+    /// Press duration after which a hold is recognized; must be longer than
+    /// [`Config::long_press`].
+    pub hold: Duration,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            active_low: true,
+            debounce: Duration::from_millis(20),
+            double_click: Duration::from_millis(300),
+            long_press: Duration::from_secs(1),
+            hold: Duration::from_secs(5),
+        }
+    }
+}
+
+// Deadlines are stored rather than durations, so that `next_event()` can resume where a
+// cancelled call left off.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum State {
+    Released,
+    Pressed { since: Instant },
+    Clicked { until: Instant },
+    LongPressed { hold_at: Instant },
+    WaitingForRelease,
+}
+
+/// Push button recognizing gestures.
+///
+/// See the [module documentation](self).
+pub struct Button<P> {
+    pin: P,
+    config: Config,
+    state: State,
+}
+
+impl<P: Wait + InputPin> Button<P> {
+    /// Creates a new button from an input pin.
+    ///
+    /// The button must be released at that time.
+    ///
+    /// # Panics
+    ///
+    /// Panics if [`Config::hold`] is not longer than [`Config::long_press`].
+    pub fn new(pin: P, config: Config) -> Self {
+        assert!(
+            config.hold > config.long_press,
+            "the hold duration must be longer than the long press duration"
+        );
+
+        Self {
+            pin,
+            config,
+            state: State::Released,
+        }
+    }
+
+    /// Returns the underlying pin.
+    pub fn into_inner(self) -> P {
+        self.pin
+    }
+
+    /// Waits for the next gesture.
+    ///
+    /// This is cancel-safe: a gesture in progress when the returned future is dropped is
+    /// recognized by the next call.
+    pub async fn next_event(&mut self) -> Result<Event, P::Error> {
+        loop {
+            match self.state {
+                State::Released => {
+                    self.wait_for_press().await?;
+                    self.state = State::Pressed {
+                        since: Instant::now(),
+                    };
+                }
+                State::Pressed { since } => {
+                    let long_press_at = since + self.config.long_press;
+                    match with_timeout(remaining(long_press_at), self.wait_for_release()).await {
+                        Ok(res) => {
+                            res?;
+                            self.state = State::Clicked {
+                                until: Instant::now() + self.config.double_click,
+                            };
+                        }
+                        Err(_) => {
+                            self.state = State::LongPressed {
+                                hold_at: since + self.config.hold,
+                            };
+                            return Ok(Event::LongPress);
+                        }
+                    }
+                }
+                State::Clicked { until } => {
+                    match with_timeout(remaining(until), self.wait_for_press()).await {
+                        Ok(res) => {
+                            res?;
+                            self.state = State::WaitingForRelease;
+                            return Ok(Event::DoubleClick);
+                        }
+                        Err(_) => {
+                            self.state = State::Released;
+                            return Ok(Event::Click);
+                        }
+                    }
+                }
+                State::LongPressed { hold_at } => {
+                    match with_timeout(remaining(hold_at), self.wait_for_release()).await {
+                        Ok(res) => {
+                            res?;
+                            self.state = State::Released;
+                        }
+                        Err(_) => {
+                            self.state = State::WaitingForRelease;
+                            return Ok(Event::Hold);
+                        }
+                    }
+                }
+                State::WaitingForRelease => {
+                    self.wait_for_release().await?;
+                    self.state = State::Released;
+                }
+            }
+        }
+    }
+
+    async fn wait_for_press(&mut self) -> Result<(), P::Error> {
+        let low = self.config.active_low;
+        self.wait_for_level(low).await
+    }
+
+    async fn wait_for_release(&mut self) -> Result<(), P::Error> {
+        let low = !self.config.active_low;
+        self.wait_for_level(low).await
+    }
+
+    /// Waits for the pin to be at the given level for the debounce time.
+    async fn wait_for_level(&mut self, low: bool) -> Result<(), P::Error> {
+        loop {
+            if low {
+                self.pin.wait_for_low().await?;
+            } else {
+                self.pin.wait_for_high().await?;
+            }
+            Timer::after(self.config.debounce).await;
+            if self.pin.is_low()? == low {
+                return Ok(());
+            }
+        }
+    }
+}
+
+fn remaining(deadline: Instant) -> Duration {
+    deadline.saturating_duration_since(Instant::now())
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use core::{future::Future, task::Poll};
+
+    use embassy_time::Instant;
+    use embedded_hal::digital::{ErrorKind, ErrorType};
+
+    use super::*;
+
+    const STEP: Duration = Duration::from_millis(1);
+
+    #[derive(Copy, Clone, Debug, PartialEq, Eq)]
+    struct PinError;
+
+    impl embedded_hal::digital::Error for PinError {
+        fn kind(&self) -> ErrorKind {
+            ErrorKind::Other
+        }
+    }
+
+    /// Active-low pin following a script of press and release times (in ms since `start`),
+    /// failing to wait for releases from `release_error_at` on.
+    ///
+    /// Waiting does not register wakers, as the simulation polls at every step.
+    struct MockPin {
+        start: Instant,
+        presses: &'static [(u64, u64)],
+        release_error_at: Option<u64>,
+    }
+
+    impl MockPin {
+        fn new(presses: &'static [(u64, u64)]) -> Self {
+            Self {
+                // Set once the simulation starts.
+                start: Instant::MIN,
+                presses,
+                release_error_at: None,
+            }
+        }
+
+        fn elapsed_ms(&self) -> u64 {
+            Instant::now().duration_since(self.start).as_millis()
+        }
+
+        fn wait_for(&mut self, low: bool) -> impl Future<Output = Result<(), PinError>> + '_ {
+            core::future::poll_fn(move |_| {
+                if !low
+                    && self
+                        .release_error_at
+                        .is_some_and(|error_at| self.elapsed_ms() >= error_at)
+                {
+                    return Poll::Ready(Err(PinError));
+                }
+                if self.is_low().unwrap() == low {
+                    Poll::Ready(Ok(()))
+                } else {
+                    Poll::Pending
+                }
+            })
+        }
+    }
+
+    impl ErrorType for MockPin {
+        type Error = PinError;
+    }
+
+    impl InputPin for MockPin {
+        fn is_high(&mut self) -> Result<bool, Self::Error> {
+            self.is_low().map(|low| !low)
+        }
+
+        fn is_low(&mut self) -> Result<bool, Self::Error> {
+            let now = self.elapsed_ms();
+            Ok(self
+                .presses
+                .iter()
+                .any(|&(pressed, released)| (pressed..released).contains(&now)))
+        }
+    }
+
+    impl Wait for MockPin {
+        async fn wait_for_high(&mut self) -> Result<(), Self::Error> {
+            self.wait_for(false).await
+        }
+
+        async fn wait_for_low(&mut self) -> Result<(), Self::Error> {
+            self.wait_for(true).await
+        }
+
+        async fn wait_for_rising_edge(&mut self) -> Result<(), Self::Error> {
+            // Not used by `Button`.
+            Err(PinError)
+        }
+
+        async fn wait_for_falling_edge(&mut self) -> Result<(), Self::Error> {
+            // Not used by `Button`.
+            Err(PinError)
+        }
+
+        async fn wait_for_any_edge(&mut self) -> Result<(), Self::Error> {
+            // Not used by `Button`.
+            Err(PinError)
+        }
+    }
+
+    /// Returns the first `count` results of a button on `pin` and when they were returned (in
+    /// ms since the start of the script), on a simulated clock.
+    fn results(mut pin: MockPin, count: usize) -> std::vec::Vec<(Result<Event, PinError>, u64)> {
+        riot_rs_test_time::run(STEP, Duration::from_secs(60), async move {
+            pin.start = Instant::now();
+            let start = pin.start;
+            let mut button = Button::new(pin, Config::default());
+            let mut results = std::vec::Vec::new();
+            for _ in 0..count {
+                let result = button.next_event().await;
+                results.push((result, start.elapsed().as_millis()));
+            }
+            results
+        })
+    }
+
+    fn events(pin: MockPin, count: usize) -> std::vec::Vec<(Event, u64)> {
+        results(pin, count)
+            .into_iter()
+            .map(|(result, time)| (result.unwrap(), time))
+            .collect()
+    }
+
+    #[test]
+    fn test_click() {
+        let pin = MockPin::new(&[(10, 100), (1000, 1050)]);
+
+        // A click is returned once the double-click delay has elapsed after the release.
+        let [(first, first_at), (second, _)] = events(pin, 2)[..] else {
+            unreachable!();
+        };
+        assert_eq!(first, Event::Click);
+        assert!((420..450).contains(&first_at), "{first_at}");
+        assert_eq!(second, Event::Click);
+    }
+
+    #[test]
+    fn test_double_click() {
+        let pin = MockPin::new(&[(10, 100), (200, 300), (1000, 1050)]);
+
+        let [(first, first_at), (second, _)] = events(pin, 2)[..] else {
+            unreachable!();
+        };
+        // Returned on the second press, after debouncing.
+        assert_eq!(first, Event::DoubleClick);
+        assert!((220..230).contains(&first_at), "{first_at}");
+        assert_eq!(second, Event::Click);
+    }
+
+    #[test]
+    fn test_long_press_and_hold() {
+        let pin = MockPin::new(&[(10, 2000), (3000, 9000), (10_000, 10_050)]);
+
+        let events = events(pin, 4);
+        let kinds: std::vec::Vec<_> = events.iter().map(|(event, _)| *event).collect();
+        assert_eq!(
+            kinds,
+            [
+                Event::LongPress,
+                Event::LongPress,
+                Event::Hold,
+                Event::Click
+            ]
+        );
+        let [_, _, (_, hold_at), _] = events[..] else {
+            unreachable!();
+        };
+        assert!((8020..8050).contains(&hold_at), "{hold_at}");
+    }
+
+    #[test]
+    fn test_bounce_ignored() {
+        // The first press is shorter than the debounce time.
+        let pin = MockPin::new(&[(10, 15), (1000, 1050)]);
+
+        let [(event, at)] = events(pin, 1)[..] else {
+            unreachable!();
+        };
+        assert_eq!(event, Event::Click);
+        assert!((1370..1400).contains(&at), "{at}");
+    }
+
+    #[test]
+    fn test_cancelled_during_press() {
+        let mut pin = MockPin::new(&[(10, 2000)]);
+
+        let (cancelled, (event, at)) =
+            riot_rs_test_time::run(STEP, Duration::from_secs(60), async move {
+                pin.start = Instant::now();
+                let start = pin.start;
+                let mut button = Button::new(pin, Config::default());
+                let cancelled = with_timeout(Duration::from_millis(500), button.next_event()).await;
+                let event = button.next_event().await;
+                (cancelled, (event, start.elapsed().as_millis()))
+            });
+        assert!(cancelled.is_err());
+        // The long press is still measured from the beginning of the press.
+        assert_eq!(event, Ok(Event::LongPress));
+        assert!((1030..1040).contains(&at), "{at}");
+    }
+
+    #[test]
+    #[should_panic(expected = "hold duration must be longer")]
+    fn test_hold_shorter_than_long_press() {
+        let config = Config {
+            hold: Duration::from_millis(500),
+            ..Config::default()
+        };
+        Button::new(MockPin::new(&[]), config);
+    }
+
+    #[test]
+    fn test_pin_error_during_press() {
+        let mut pin = MockPin::new(&[(10, 5000)]);
+        pin.release_error_at = Some(500);
+
+        // The error must be returned instead of being mistaken for a release, which would
+        // then be followed by a double-click as the pin is still low.
+        let [(res, at)] = results(pin, 1)[..] else {
+            unreachable!();
+        };
+        assert_eq!(res, Err(PinError));
+        assert!((500..510).contains(&at), "{at}");
+    }
+}
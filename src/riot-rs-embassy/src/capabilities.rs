@@ -0,0 +1,73 @@
+//! Describes what the MCU of the current architecture supports.
+//!
+//! Use [`arch::capabilities()`](crate::arch::capabilities) to obtain the capabilities of the
+//! current architecture, e.g., to validate a configuration in a `const` context instead of
+//! relying on architecture-specific constants.
+//! The RIOT-rs macros check the capabilities the same way, e.g., to reject a `usb_builder_hook`
+//! on an MCU without USB device controller.
+
+/// Capabilities of an MCU.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Capabilities {
+    /// GPIO capabilities.
+    pub gpio: GpioCapabilities,
+    /// Number of I2C bus controllers.
+    pub i2c_bus_count: u8,
+    /// Number of SPI bus controllers.
+    pub spi_bus_count: u8,
+    /// Number of UARTs.
+    ///
+    /// Bus controllers and UARTs may share peripheral instances, in which case they cannot all be
+    /// used at the same time.
+    pub uart_count: u8,
+    /// Maximum SPI clock frequency supported by the fastest SPI bus controller (in Hz).
+    pub max_spi_frequency: u32,
+    /// Whether the MCU has a USB device controller.
+    pub usb_device: bool,
+}
+
+/// GPIO capabilities of an MCU.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct GpioCapabilities {
+    /// Number of GPIO pins.
+    pub pin_count: u8,
+    /// Number of EXTI channels, each of which can detect edges on one pin.
+    pub exti_channel_count: u8,
+    /// Whether all pins can be waited on for level changes concurrently, independently of the
+    /// EXTI channels.
+    pub wait_on_all_pins: bool,
+    /// Whether the Schmitt trigger of inputs can be enabled or disabled.
+    pub schmitt_trigger_configurable: bool,
+    /// Whether the drive strength of outputs can be configured.
+    pub drive_strength_configurable: bool,
+    /// Whether inputs can be pulled down (in addition to being pulled up).
+    pub pull_down: bool,
+}
+
+// Checks the capabilities of the current architecture for consistency.
+const _: () = {
+    let capabilities = crate::arch::capabilities();
+    assert!(
+        capabilities.gpio.exti_channel_count <= capabilities.gpio.pin_count,
+        "more EXTI channels than GPIO pins"
+    );
+};
+
+impl Capabilities {
+    /// Capabilities of an MCU that supports nothing.
+    pub const NONE: Self = Self {
+        gpio: GpioCapabilities {
+            pin_count: 0,
+            exti_channel_count: 0,
+            wait_on_all_pins: false,
+            schmitt_trigger_configurable: false,
+            drive_strength_configurable: false,
+            pull_down: false,
+        },
+        i2c_bus_count: 0,
+        spi_bus_count: 0,
+        uart_count: 0,
+        max_spi_frequency: 0,
+        usb_device: false,
+    };
+}
@@ -1,9 +1,83 @@
+//! Provides macros to extract peripherals from `OptionalPeripherals`, while keeping track of
+//! which code took them.
+//!
+//! When a peripheral is requested that has already been taken, the resulting panic message names
+//! the previous taker, when known.
+
+use core::cell::{Cell, RefCell};
+
+use embassy_sync::blocking_mutex::CriticalSectionMutex;
+
+/// Maximum number of peripherals whose taker can be recorded.
+const MAX_TRACKED_PERIPHERALS: usize = riot_rs_utils::usize_from_env_or!(
+    "CONFIG_MAX_TRACKED_PERIPHERALS",
+    32,
+    "maximum number of peripherals whose taker is recorded"
+);
+
+/// Taker name used when no taker has been set through [`with_taker()`].
+const UNKNOWN_TAKER: &str = "<unknown>";
+
+static CURRENT_TAKER: CriticalSectionMutex<Cell<&'static str>> =
+    CriticalSectionMutex::new(Cell::new(UNKNOWN_TAKER));
+
+/// Peripheral names, along with the name of the code that took them.
+static TAKERS: CriticalSectionMutex<
+    RefCell<heapless::Vec<(&'static str, &'static str), MAX_TRACKED_PERIPHERALS>>,
+> = CriticalSectionMutex::new(RefCell::new(heapless::Vec::new()));
+
+/// Runs `f`, recording `taker` as the taker of all peripherals taken with [`take()`] in the
+/// meantime.
+///
+/// This is used by the `riot_rs::task` and `riot_rs::spawner` macros, which pass the name of the
+/// function being started.
+pub fn with_taker<R>(taker: &'static str, f: impl FnOnce() -> R) -> R {
+    let previous_taker = CURRENT_TAKER.lock(|current| current.replace(taker));
+    let res = f();
+    CURRENT_TAKER.lock(|current| current.set(previous_taker));
+    res
+}
+
+/// Takes a peripheral out of its `OptionalPeripherals` field, recording the current taker.
+///
+/// # Panics
+///
+/// Panics if the peripheral has already been taken, naming the previous taker when it is known.
+pub fn take<T>(peripheral: &mut Option<T>, name: &'static str) -> T {
+    let taker = CURRENT_TAKER.lock(Cell::get);
+
+    if let Some(peripheral) = peripheral.take() {
+        // If the list is full, the taker is simply not recorded.
+        let _ = TAKERS.lock(|takers| takers.borrow_mut().push((name, taker)));
+        return peripheral;
+    }
+
+    let previous_taker = TAKERS.lock(|takers| {
+        takers
+            .borrow()
+            .iter()
+            .find(|(peripheral_name, _)| *peripheral_name == name)
+            .map(|(_, previous_taker)| *previous_taker)
+    });
+
+    if let Some(previous_taker) = previous_taker {
+        panic!(
+            "peripheral `{name}` requested by `{taker}` was already taken by `{previous_taker}`"
+        );
+    } else {
+        panic!("peripheral `{name}` requested by `{taker}` was already taken");
+    }
+}
+
 /// This macro allows to extract the specified peripherals from `OptionalPeripherals` for use in an
 /// application.
 ///
 /// The generated struct can be obtained by calling the `take_peripherals()` method on
 /// `&mut OptionalPeripherals`.
 ///
+/// Taking a peripheral that has already been taken panics with a message naming the previous
+/// taker.
+///
 /// The `define_peripherals!` macro expects a `peripherals` module to be in scope, where the
 /// peripheral types should come from.
 ///
@@ -45,7 +119,10 @@ macro_rules! define_peripherals {
                 $peripherals {
                     $(
                         $(#[$inner])*
-                        $peripheral_name: self.$peripheral_field.take().unwrap()
+                        $peripheral_name: $crate::define_peripherals::take(
+                            &mut self.$peripheral_field,
+                            stringify!($peripheral_field),
+                        )
                     ),*
                 }
             }
@@ -0,0 +1,218 @@
+//! Provides a portable async I2C controller.
+//!
+//! An [`I2c`] controller is obtained from the `i2c::new()` function of the architecture, which
+//! takes an I2C peripheral instance (`TWISPIn` on nRF52, `SERIALn` on nRF5340, `I2Cn` on RP2040,
+//! `I2C0` on ESP) and the SDA and SCL pins.
+//! It implements [`embedded_hal_async::i2c::I2c`], with a timeout on each transaction.
+//! On nRF and ESP, a [`transaction()`](embedded_hal_async::i2c::I2c::transaction) can only
+//! consist of writes followed by reads, and adjacent operations of the same type can be at most
+//! `CONFIG_I2C_TRANSACTION_BUF_SIZE` bytes (64 by default) long in total; other transactions fail
+//! with [`Error::UnsupportedTransaction`] and [`Error::TransactionTooLong`] respectively.
+//!
+//! To share a bus between multiple devices, put the controller into an [`I2cBus`], and create an
+//! [`I2cDevice`] for each device.
+//!
+//! Example:
+//! ```Rust
+//! riot_rs::define_peripherals!(SensorPeripherals {
+//!     i2c: TWISPI0,
+//!     sda: P0_26,
+//!     scl: P0_27,
+//! });
+//!
+//! static I2C_BUS: StaticCell<I2cBus> = StaticCell::new();
+//!
+//! let config = i2c::Config::default();
+//! let i2c = arch::i2c::new(peripherals.i2c, peripherals.sda, peripherals.scl, &config);
+//! let i2c_bus = I2C_BUS.init(I2cBus::new(i2c));
+//! let sensor_i2c = I2cDevice::new(i2c_bus);
+//! ```
+
+use embassy_embedded_hal::shared_bus::asynch::i2c::I2cDevice as SharedI2cDevice;
+use embassy_sync::{blocking_mutex::raw::CriticalSectionRawMutex, mutex::Mutex};
+use embassy_time::{with_timeout, Duration};
+use embedded_hal_async::i2c::{self as hal_i2c, ErrorKind, ErrorType, Operation, SevenBitAddress};
+
+use crate::arch;
+
+// Only used by architectures whose controllers cannot execute arbitrary transactions.
+#[cfg_attr(not(any(context = "nrf", context = "esp")), allow(dead_code))]
+pub(crate) mod transaction;
+
+#[cfg(context = "riot-rs")]
+const _: () = assert!(
+    arch::capabilities().i2c_bus_count > 0,
+    "the MCU has no I2C bus controller"
+);
+
+/// I2C bus frequency.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Frequency {
+    /// Standard mode (100 kHz).
+    K100,
+    /// Fast mode (400 kHz).
+    K400,
+}
+
+impl Frequency {
+    /// Returns the frequency in hertz.
+    pub const fn to_hz(self) -> u32 {
+        match self {
+            Self::K100 => 100_000,
+            Self::K400 => 400_000,
+        }
+    }
+}
+
+/// I2C bus configuration.
+#[derive(Copy, Clone, Debug)]
+#[non_exhaustive]
+pub struct Config {
+    /// Frequency of the bus.
+    pub frequency: Frequency,
+    /// Whether to enable the internal pull-up resistors of SDA and SCL.
+    ///
+    /// Internal pull-ups are weak and external ones are recommended, especially in fast mode.
+    /// On RP2040 and ESP, the internal pull-ups are always enabled.
+    pub internal_pull_ups: bool,
+    /// Maximum duration of a single transaction, after which it fails with [`Error::Timeout`].
+    pub timeout: Duration,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            frequency: Frequency::K100,
+            internal_pull_ups: false,
+            timeout: Duration::from_millis(100),
+        }
+    }
+}
+
+/// Possible errors of I2C transactions.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Error {
+    /// The bus driver reported an error.
+    Bus(ErrorKind),
+    /// The transaction did not complete within the configured timeout.
+    Timeout,
+    /// The transaction has a write following a read, which the controller cannot execute.
+    UnsupportedTransaction,
+    /// Adjacent operations of the same type in the transaction are longer than
+    /// `CONFIG_I2C_TRANSACTION_BUF_SIZE` bytes in total.
+    TransactionTooLong,
+}
+
+impl<E: hal_i2c::Error> From<transaction::Error<E>> for Error {
+    fn from(err: transaction::Error<E>) -> Self {
+        match err {
+            transaction::Error::Controller(err) => Self::Bus(err.kind()),
+            transaction::Error::UnsupportedTransaction => Self::UnsupportedTransaction,
+            transaction::Error::TooLong => Self::TransactionTooLong,
+        }
+    }
+}
+
+impl hal_i2c::Error for Error {
+    fn kind(&self) -> ErrorKind {
+        match self {
+            Self::Bus(kind) => *kind,
+            Self::Timeout | Self::UnsupportedTransaction | Self::TransactionTooLong => {
+                ErrorKind::Other
+            }
+        }
+    }
+}
+
+impl core::fmt::Display for Error {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Bus(kind) => write!(f, "I2C bus error: {kind}"),
+            Self::Timeout => write!(f, "I2C transaction timed out"),
+            Self::UnsupportedTransaction => write!(f, "I2C transaction not supported"),
+            Self::TransactionTooLong => write!(f, "I2C transaction too long"),
+        }
+    }
+}
+
+/// Async I2C controller.
+///
+/// See the [module documentation](self) for how to obtain one.
+pub struct I2c {
+    inner: arch::i2c::I2c,
+    timeout: Duration,
+}
+
+impl I2c {
+    #[cfg_attr(not(context = "riot-rs"), allow(dead_code))]
+    pub(crate) fn new(inner: arch::i2c::I2c, config: &Config) -> Self {
+        Self {
+            inner,
+            timeout: config.timeout,
+        }
+    }
+}
+
+impl ErrorType for I2c {
+    type Error = Error;
+}
+
+// The methods are forwarded individually, as some architectures only implement some of them.
+impl hal_i2c::I2c<SevenBitAddress> for I2c {
+    async fn read(&mut self, address: u8, read: &mut [u8]) -> Result<(), Self::Error> {
+        with_timeout(
+            self.timeout,
+            hal_i2c::I2c::read(&mut self.inner, address, read),
+        )
+        .await
+        .map_err(|_| Error::Timeout)?
+        .map_err(Error::from)
+    }
+
+    async fn write(&mut self, address: u8, write: &[u8]) -> Result<(), Self::Error> {
+        with_timeout(
+            self.timeout,
+            hal_i2c::I2c::write(&mut self.inner, address, write),
+        )
+        .await
+        .map_err(|_| Error::Timeout)?
+        .map_err(Error::from)
+    }
+
+    async fn write_read(
+        &mut self,
+        address: u8,
+        write: &[u8],
+        read: &mut [u8],
+    ) -> Result<(), Self::Error> {
+        with_timeout(
+            self.timeout,
+            hal_i2c::I2c::write_read(&mut self.inner, address, write, read),
+        )
+        .await
+        .map_err(|_| Error::Timeout)?
+        .map_err(Error::from)
+    }
+
+    async fn transaction(
+        &mut self,
+        address: u8,
+        operations: &mut [Operation<'_>],
+    ) -> Result<(), Self::Error> {
+        with_timeout(
+            self.timeout,
+            hal_i2c::I2c::transaction(&mut self.inner, address, operations),
+        )
+        .await
+        .map_err(|_| Error::Timeout)?
+        .map_err(Error::from)
+    }
+}
+
+/// I2C bus shared between multiple [`I2cDevice`]s.
+pub type I2cBus = Mutex<CriticalSectionRawMutex, I2c>;
+
+/// Device on an [`I2cBus`].
+///
+/// Each transaction locks the bus for its whole duration.
+pub type I2cDevice = SharedI2cDevice<'static, CriticalSectionRawMutex, I2c>;
@@ -0,0 +1,259 @@
+//! Executes transactions on controllers that cannot emit a repeated START condition between
+//! arbitrary operations.
+//!
+//! Such controllers can still execute a single write, a single read, or a write followed by a
+//! read, all within one transfer.
+//! Adjacent operations of the same type are therefore merged through buffers, so that no START or
+//! STOP condition is emitted between them; this requires all writes to come before all reads.
+
+use embedded_hal_async::i2c::{self as hal_i2c, ErrorKind, Operation};
+
+/// Size of the buffers used to merge adjacent operations of the same type, in bytes.
+pub(crate) const BUF_SIZE: usize = riot_rs_utils::usize_from_env_or!(
+    "CONFIG_I2C_TRANSACTION_BUF_SIZE",
+    64,
+    "size (in bytes) of the buffers used to merge I2C operations in transactions"
+);
+
+/// Transfers a controller can execute without emitting a STOP condition.
+pub(crate) trait Transfers {
+    type Error;
+
+    async fn write(&mut self, address: u8, write: &[u8]) -> Result<(), Self::Error>;
+
+    async fn read(&mut self, address: u8, read: &mut [u8]) -> Result<(), Self::Error>;
+
+    /// Writes, then reads after a repeated START condition.
+    async fn write_read(
+        &mut self,
+        address: u8,
+        write: &[u8],
+        read: &mut [u8],
+    ) -> Result<(), Self::Error>;
+}
+
+/// Possible errors of [`execute()`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Error<E> {
+    /// The controller reported an error.
+    Controller(E),
+    /// A write follows a read.
+    UnsupportedTransaction,
+    /// Adjacent operations of the same type are longer than the merge buffers.
+    TooLong,
+}
+
+impl<E: hal_i2c::Error> hal_i2c::Error for Error<E> {
+    fn kind(&self) -> ErrorKind {
+        match self {
+            Self::Controller(err) => err.kind(),
+            Self::UnsupportedTransaction | Self::TooLong => ErrorKind::Other,
+        }
+    }
+}
+
+/// Executes `operations` as a single transfer of `controller`.
+pub(crate) async fn execute<C: Transfers>(
+    controller: &mut C,
+    address: u8,
+    operations: &mut [Operation<'_>],
+) -> Result<(), Error<C::Error>> {
+    let first_read = operations
+        .iter()
+        .position(|op| matches!(op, Operation::Read(_)))
+        .unwrap_or(operations.len());
+    let (writes, reads) = operations.split_at_mut(first_read);
+    if reads.iter().any(|op| matches!(op, Operation::Write(_))) {
+        return Err(Error::UnsupportedTransaction);
+    }
+
+    let mut write_buf = [0; BUF_SIZE];
+    let write = match writes {
+        [] => None,
+        [Operation::Write(write)] => Some(&**write),
+        _ => Some(gather(writes, &mut write_buf)?),
+    };
+
+    let res = match (write, reads) {
+        (None, []) => Ok(()),
+        (Some(write), []) => controller.write(address, write).await,
+        (None, [Operation::Read(read)]) => controller.read(address, read).await,
+        (Some(write), [Operation::Read(read)]) => controller.write_read(address, write, read).await,
+        (write, reads) => {
+            let mut read_buf = [0; BUF_SIZE];
+            let len = reads.iter().map(operation_len).sum();
+            let read = read_buf.get_mut(..len).ok_or(Error::TooLong)?;
+            let res = match write {
+                Some(write) => controller.write_read(address, write, read).await,
+                None => controller.read(address, read).await,
+            };
+            if res.is_ok() {
+                scatter(read, reads);
+            }
+            res
+        }
+    };
+    res.map_err(Error::Controller)
+}
+
+fn operation_len(operation: &Operation<'_>) -> usize {
+    match operation {
+        Operation::Read(read) => read.len(),
+        Operation::Write(write) => write.len(),
+    }
+}
+
+/// Copies the data of the write operations `writes` into `buf`, and returns the filled part.
+fn gather<'b, E>(writes: &[Operation<'_>], buf: &'b mut [u8]) -> Result<&'b [u8], Error<E>> {
+    let mut len = 0;
+    for op in writes {
+        if let Operation::Write(write) = op {
+            buf.get_mut(len..len + write.len())
+                .ok_or(Error::TooLong)?
+                .copy_from_slice(write);
+            len += write.len();
+        }
+    }
+    Ok(buf.split_at(len).0)
+}
+
+/// Copies `data` into the buffers of the read operations `reads`.
+fn scatter(mut data: &[u8], reads: &mut [Operation<'_>]) {
+    for op in reads {
+        if let Operation::Read(read) = op {
+            let (head, tail) = data.split_at(read.len());
+            read.copy_from_slice(head);
+            data = tail;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use std::{vec, vec::Vec};
+
+    use embassy_futures::block_on;
+
+    use super::*;
+
+    #[derive(Debug, PartialEq, Eq)]
+    enum Transfer {
+        Write(Vec<u8>),
+        Read(usize),
+        WriteRead(Vec<u8>, usize),
+    }
+
+    /// Records the transfers, and reads an incrementing sequence of bytes.
+    #[derive(Default)]
+    struct MockController {
+        transfers: Vec<Transfer>,
+    }
+
+    fn fill(read: &mut [u8]) {
+        for (i, byte) in read.iter_mut().enumerate() {
+            *byte = i as u8;
+        }
+    }
+
+    impl Transfers for MockController {
+        type Error = ();
+
+        async fn write(&mut self, _address: u8, write: &[u8]) -> Result<(), ()> {
+            self.transfers.push(Transfer::Write(write.to_vec()));
+            Ok(())
+        }
+
+        async fn read(&mut self, _address: u8, read: &mut [u8]) -> Result<(), ()> {
+            self.transfers.push(Transfer::Read(read.len()));
+            fill(read);
+            Ok(())
+        }
+
+        async fn write_read(
+            &mut self,
+            _address: u8,
+            write: &[u8],
+            read: &mut [u8],
+        ) -> Result<(), ()> {
+            self.transfers
+                .push(Transfer::WriteRead(write.to_vec(), read.len()));
+            fill(read);
+            Ok(())
+        }
+    }
+
+    fn run(operations: &mut [Operation<'_>]) -> (Result<(), Error<()>>, Vec<Transfer>) {
+        let mut controller = MockController::default();
+        let res = block_on(execute(&mut controller, 0x42, operations));
+        (res, controller.transfers)
+    }
+
+    #[test]
+    fn test_single_operations() {
+        let mut read = [0; 2];
+        let (res, transfers) = run(&mut [Operation::Write(&[1, 2])]);
+        assert_eq!(res, Ok(()));
+        assert_eq!(transfers, vec![Transfer::Write(vec![1, 2])]);
+
+        let (res, transfers) = run(&mut [Operation::Read(&mut read)]);
+        assert_eq!(res, Ok(()));
+        assert_eq!(transfers, vec![Transfer::Read(2)]);
+        assert_eq!(read, [0, 1]);
+
+        let (res, transfers) = run(&mut [Operation::Write(&[1]), Operation::Read(&mut read)]);
+        assert_eq!(res, Ok(()));
+        assert_eq!(transfers, vec![Transfer::WriteRead(vec![1], 2)]);
+
+        let (res, transfers) = run(&mut []);
+        assert_eq!(res, Ok(()));
+        assert_eq!(transfers, vec![]);
+    }
+
+    #[test]
+    fn test_merges_adjacent_operations() {
+        let mut read1 = [0; 2];
+        let mut read2 = [0; 3];
+        let (res, transfers) = run(&mut [
+            Operation::Write(&[1, 2]),
+            Operation::Write(&[3]),
+            Operation::Read(&mut read1),
+            Operation::Read(&mut read2),
+        ]);
+        assert_eq!(res, Ok(()));
+        assert_eq!(transfers, vec![Transfer::WriteRead(vec![1, 2, 3], 5)]);
+        assert_eq!(read1, [0, 1]);
+        assert_eq!(read2, [2, 3, 4]);
+
+        let (res, transfers) = run(&mut [Operation::Read(&mut read1), Operation::Read(&mut read2)]);
+        assert_eq!(res, Ok(()));
+        assert_eq!(transfers, vec![Transfer::Read(5)]);
+    }
+
+    #[test]
+    fn test_write_after_read() {
+        let mut read = [0; 2];
+        let (res, transfers) = run(&mut [Operation::Read(&mut read), Operation::Write(&[1])]);
+        assert_eq!(res, Err(Error::UnsupportedTransaction));
+        assert_eq!(transfers, vec![]);
+    }
+
+    #[test]
+    fn test_too_long() {
+        let write = [0; BUF_SIZE];
+        let (res, transfers) = run(&mut [Operation::Write(&write), Operation::Write(&[1])]);
+        assert_eq!(res, Err(Error::TooLong));
+        assert_eq!(transfers, vec![]);
+
+        let mut read1 = [0; BUF_SIZE];
+        let mut read2 = [0; 1];
+        let (res, transfers) = run(&mut [Operation::Read(&mut read1), Operation::Read(&mut read2)]);
+        assert_eq!(res, Err(Error::TooLong));
+        assert_eq!(transfers, vec![]);
+
+        // Single operations are not merged, so they are not limited.
+        let (res, _) = run(&mut [Operation::Write(&write), Operation::Read(&mut read1)]);
+        assert_eq!(res, Ok(()));
+    }
+}
@@ -0,0 +1,17 @@
+//! Periodic overflow check of the ISR stack.
+
+use embassy_time::{Duration, Timer};
+
+const CHECK_INTERVAL_MS: u64 = riot_rs_utils::usize_from_env_or!(
+    "CONFIG_ISR_STACK_CHECK_INTERVAL_MS",
+    1000,
+    "interval (in milliseconds) between two checks of the ISR stack canary"
+) as u64;
+
+#[embassy_executor::task]
+pub(crate) async fn isr_stack_check_task() -> ! {
+    loop {
+        riot_rs_rt::isr_stack::check();
+        Timer::after(Duration::from_millis(CHECK_INTERVAL_MS)).await;
+    }
+}
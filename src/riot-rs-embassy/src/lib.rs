@@ -4,6 +4,7 @@
 #![feature(type_alias_impl_trait)]
 #![feature(used_with_arg)]
 
+pub mod capabilities;
 pub mod define_peripherals;
 
 #[cfg(context = "cortex-m")]
@@ -27,6 +28,18 @@ cfg_if::cfg_if! {
     }
 }
 
+#[cfg(feature = "button")]
+pub mod button;
+
+#[cfg(feature = "i2c")]
+pub mod i2c;
+
+#[cfg(feature = "isr-stack-canary")]
+mod isr_stack;
+
+#[cfg(feature = "uart")]
+pub mod uart;
+
 #[cfg(feature = "usb")]
 pub mod usb;
 
@@ -123,59 +136,18 @@ async fn init_task(mut peripherals: arch::OptionalPeripherals) {
 
     let spawner = Spawner::for_current_executor().await;
 
+    #[cfg(feature = "isr-stack-canary")]
+    spawner.spawn(isr_stack::isr_stack_check_task()).unwrap();
+
     for task in EMBASSY_TASKS {
         task(spawner, &mut peripherals);
     }
 
     #[cfg(feature = "usb")]
-    let mut usb_builder = {
-        let usb_config = usb::config();
-
-        let usb_driver = arch::usb::driver(&mut peripherals);
-
-        // Create embassy-usb DeviceBuilder using the driver and config.
-        let builder = usb::UsbBuilder::new(
-            usb_driver,
-            usb_config,
-            &mut make_static!([0; 256])[..],
-            &mut make_static!([0; 256])[..],
-            &mut make_static!([0; 256])[..],
-            &mut make_static!([0; 128])[..],
-            &mut make_static!([0; 128])[..],
-        );
-
-        builder
-    };
+    let mut usb_builder = usb::builder(arch::usb::driver(&mut peripherals));
 
     #[cfg(feature = "usb-ethernet")]
-    let device = {
-        use embassy_usb::class::cdc_ncm::{
-            embassy_net::State as NetState, CdcNcmClass, State as CdcNcmState,
-        };
-
-        // Host's MAC addr. This is the MAC the host "thinks" its USB-to-ethernet adapter has.
-        let host_mac_addr = [0x8A, 0x88, 0x88, 0x88, 0x88, 0x88];
-
-        // Create classes on the builder.
-        let usb_cdc_ecm = CdcNcmClass::new(
-            &mut usb_builder,
-            make_static!(CdcNcmState::new()),
-            host_mac_addr,
-            64,
-        );
-
-        let our_mac_addr = [0xCA, 0xCC, 0xCC, 0xCC, 0xCC, 0xCC];
-
-        let (runner, device) = usb_cdc_ecm
-            .into_embassy_net_device::<{ network::ETHERNET_MTU }, 4, 4>(
-                make_static!(NetState::new()),
-                our_mac_addr,
-            );
-
-        spawner.spawn(usb::ethernet::usb_ncm_task(runner)).unwrap();
-
-        device
-    };
+    let device = usb::ethernet::device(&mut usb_builder, spawner);
 
     #[cfg(feature = "usb")]
     {
@@ -196,43 +168,7 @@ async fn init_task(mut peripherals: arch::OptionalPeripherals) {
     let device = wifi::esp_wifi::init(&mut peripherals, spawner);
 
     #[cfg(feature = "net")]
-    {
-        use crate::network::STACK;
-        use crate::sendcell::SendCell;
-        use embassy_net::{Stack, StackResources};
-
-        const MAX_CONCURRENT_SOCKETS: usize = riot_rs_utils::usize_from_env_or!(
-            "CONFIG_NETWORK_MAX_CONCURRENT_SOCKETS",
-            4,
-            "maximum number of concurrent sockets allowed by the network stack"
-        );
-
-        let config = network::config();
-
-        // Generate random seed
-        // let mut rng = Rng::new(p.RNG, Irqs);
-        // let mut seed = [0; 8];
-        // rng.blocking_fill_bytes(&mut seed);
-        // let seed = u64::from_le_bytes(seed);
-        let seed = 1234u64;
-
-        // Init network stack
-        let stack = &*make_static!(Stack::new(
-            device,
-            config,
-            make_static!(StackResources::<MAX_CONCURRENT_SOCKETS>::new()),
-            seed
-        ));
-
-        spawner.spawn(network::net_task(stack)).unwrap();
-
-        if STACK
-            .lock(|c| c.set(SendCell::new(stack, spawner)))
-            .is_err()
-        {
-            unreachable!();
-        }
-    }
+    network::init(device, spawner);
 
     #[cfg(feature = "wifi-cyw43")]
     {
@@ -3,9 +3,10 @@
 use core::cell::OnceCell;
 
 use embassy_executor::Spawner;
-use embassy_net::Stack;
+use embassy_net::{Stack, StackResources};
 use embassy_sync::blocking_mutex::CriticalSectionMutex;
 
+use crate::make_static;
 use crate::sendcell::SendCell;
 use crate::NetworkDevice;
 
@@ -14,7 +15,7 @@ pub const ETHERNET_MTU: usize = 1514;
 
 pub type NetworkStack = Stack<NetworkDevice>;
 
-pub(crate) static STACK: CriticalSectionMutex<OnceCell<SendCell<&'static NetworkStack>>> =
+static STACK: CriticalSectionMutex<OnceCell<SendCell<&'static NetworkStack>>> =
     CriticalSectionMutex::new(OnceCell::new());
 
 pub async fn network_stack() -> Option<&'static NetworkStack> {
@@ -22,12 +23,48 @@ pub async fn network_stack() -> Option<&'static NetworkStack> {
     STACK.lock(|cell| cell.get().map(|x| *x.get(spawner).unwrap()))
 }
 
+const MAX_CONCURRENT_SOCKETS: usize = riot_rs_utils::usize_from_env_or!(
+    "CONFIG_NETWORK_MAX_CONCURRENT_SOCKETS",
+    4,
+    "maximum number of concurrent sockets allowed by the network stack"
+);
+
 #[embassy_executor::task]
 pub(crate) async fn net_task(stack: &'static Stack<NetworkDevice>) -> ! {
     stack.run().await
 }
 
-pub(crate) fn config() -> embassy_net::Config {
+/// Creates the network stack on top of `device`, and makes it available through
+/// [`network_stack()`].
+pub(crate) fn init(device: NetworkDevice, spawner: Spawner) {
+    let config = config();
+
+    // Generate random seed
+    // let mut rng = Rng::new(p.RNG, Irqs);
+    // let mut seed = [0; 8];
+    // rng.blocking_fill_bytes(&mut seed);
+    // let seed = u64::from_le_bytes(seed);
+    let seed = 1234u64;
+
+    // Init network stack
+    let stack = &*make_static!(Stack::new(
+        device,
+        config,
+        make_static!(StackResources::<MAX_CONCURRENT_SOCKETS>::new()),
+        seed
+    ));
+
+    spawner.spawn(net_task(stack)).unwrap();
+
+    if STACK
+        .lock(|c| c.set(SendCell::new(stack, spawner)))
+        .is_err()
+    {
+        unreachable!();
+    }
+}
+
+fn config() -> embassy_net::Config {
     #[cfg(not(feature = "override-network-config"))]
     {
         embassy_net::Config::dhcpv4(Default::default())
@@ -0,0 +1,256 @@
+//! Provides a portable async UART.
+//!
+//! A [`Uart`] is obtained from the `uart::new()` function of the architecture, which takes a
+//! UART peripheral instance (`UARTEn` on nRF52, `SERIALn` on nRF5340, `UARTn` on RP2040 and
+//! ESP) and the RX and TX pins.
+//! On nRF, it additionally takes the timer associated with the instance (see the `Instance`
+//! trait of the architecture), two PPI channels and a PPI group, which the driver needs to count
+//! received bytes.
+//! Hardware flow control is enabled by using `uart::new_with_rtscts()` instead, which
+//! additionally takes the CTS and RTS pins.
+//! It implements [`embedded_io_async::Read`] and [`embedded_io_async::Write`].
+//!
+//! On nRF and RP2040, received and transmitted data go through ring buffers, whose sizes can be
+//! set with the `CONFIG_UART_RX_BUF_SIZE` and `CONFIG_UART_TX_BUF_SIZE` environment variables.
+//! On nRF52832, whose DMA transfers are limited to 255 bytes, the transmit buffer is capped at
+//! 255 bytes and the receive buffer at 510 bytes.
+//! Reads return as soon as some data is available, and writes return once the data has been
+//! copied to the transmit buffer.
+//! On ESP, the hardware FIFOs are used instead, and reads return once the line is idle or the
+//! receive FIFO is full.
+//!
+//! Example:
+//! ```Rust
+//! riot_rs::define_peripherals!(UartPeripherals {
+//!     uart: UART0,
+//!     rx: PIN_1,
+//!     tx: PIN_0,
+//! });
+//!
+//! let mut config = uart::Config::default();
+//! config.baudrate = 9600;
+//! config.parity = uart::Parity::Even;
+//!
+//! let mut uart = arch::uart::new(peripherals.uart, peripherals.rx, peripherals.tx, &config)?;
+//! uart.write_all(b"hello\n").await?;
+//! ```
+//!
+//! See the `usb-uart-bridge` example for a complete application.
+
+#[cfg(feature = "usb-uart-bridge")]
+use embassy_usb::class::cdc_acm::{self, LineCoding, ParityType};
+use embedded_io_async::{ErrorKind, ErrorType, Read, Write};
+
+use crate::arch;
+
+#[cfg(context = "riot-rs")]
+const _: () = assert!(arch::capabilities().uart_count > 0, "the MCU has no UART");
+
+// Sizes of the receive and transmit ring buffers, in bytes.
+#[cfg_attr(any(not(context = "riot-rs"), context = "esp"), allow(dead_code))]
+pub(crate) const RX_BUF_SIZE: usize = riot_rs_utils::usize_from_env_or!(
+    "CONFIG_UART_RX_BUF_SIZE",
+    256,
+    "size (in bytes) of the UART receive buffer"
+);
+#[cfg_attr(any(not(context = "riot-rs"), context = "esp"), allow(dead_code))]
+pub(crate) const TX_BUF_SIZE: usize = riot_rs_utils::usize_from_env_or!(
+    "CONFIG_UART_TX_BUF_SIZE",
+    256,
+    "size (in bytes) of the UART transmit buffer"
+);
+
+/// Parity bit.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Parity {
+    /// No parity bit.
+    None,
+    /// Even parity.
+    Even,
+    /// Odd parity; not supported on nRF.
+    Odd,
+}
+
+/// Number of stop bits.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum StopBits {
+    /// One stop bit.
+    One,
+    /// Two stop bits; not supported on nRF.
+    Two,
+}
+
+/// UART configuration.
+///
+/// Creating a [`Uart`] fails with a [`ConfigError`] if the architecture does not support the
+/// configuration.
+#[derive(Copy, Clone, Debug)]
+#[non_exhaustive]
+pub struct Config {
+    /// Baud rate.
+    ///
+    /// nRF only supports a fixed set of standard baud rates, from 1200 to 1000000.
+    /// RP2040 supports baud rates above 1/1048560 and up to 1/16 of the peripheral clock
+    /// frequency, and ESP from 100 to 5000000.
+    pub baudrate: u32,
+    /// Parity bit.
+    pub parity: Parity,
+    /// Number of stop bits.
+    pub stop_bits: StopBits,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            baudrate: 115_200,
+            parity: Parity::None,
+            stop_bits: StopBits::One,
+        }
+    }
+}
+
+/// Possible errors when creating or reconfiguring a [`Uart`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ConfigError {
+    /// The baud rate is not supported.
+    Baudrate,
+    /// The parity is not supported.
+    Parity,
+    /// The number of stop bits is not supported.
+    StopBits,
+}
+
+impl core::fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Baudrate => write!(f, "unsupported UART baud rate"),
+            Self::Parity => write!(f, "unsupported UART parity"),
+            Self::StopBits => write!(f, "unsupported number of UART stop bits"),
+        }
+    }
+}
+
+/// Possible errors of UART transfers.
+///
+/// Which errors are detected depends on the architecture:
+/// - RP2040 reports all of them.
+/// - ESP only reports [`Error::Overrun`].
+/// - nRF reports none of them: its driver panics on overruns, and ignores other line errors.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Error {
+    /// Received data was lost because it was not read in time.
+    Overrun,
+    /// The parity of a received word was incorrect.
+    Parity,
+    /// A received word did not have a valid stop bit.
+    Framing,
+    /// A break condition was detected.
+    Break,
+    /// The driver reported another error.
+    Other,
+}
+
+impl embedded_io_async::Error for Error {
+    fn kind(&self) -> ErrorKind {
+        match self {
+            Self::Parity | Self::Framing => ErrorKind::InvalidData,
+            Self::Overrun | Self::Break | Self::Other => ErrorKind::Other,
+        }
+    }
+}
+
+impl core::fmt::Display for Error {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Overrun => write!(f, "UART overrun"),
+            Self::Parity => write!(f, "UART parity error"),
+            Self::Framing => write!(f, "UART framing error"),
+            Self::Break => write!(f, "UART break condition"),
+            Self::Other => write!(f, "UART error"),
+        }
+    }
+}
+
+/// Async UART.
+///
+/// See the [module documentation](self) for how to obtain one.
+pub struct Uart {
+    inner: arch::uart::Uart,
+    config: Config,
+}
+
+impl Uart {
+    #[cfg_attr(not(context = "riot-rs"), allow(dead_code))]
+    pub(crate) fn new(inner: arch::uart::Uart, config: &Config) -> Self {
+        Self {
+            inner,
+            config: *config,
+        }
+    }
+
+    /// Changes the baud rate.
+    ///
+    /// Data being transferred may be corrupted.
+    /// Fails if the baud rate is not supported, in which case the previous one is kept.
+    pub fn set_baudrate(&mut self, baudrate: u32) -> Result<(), ConfigError> {
+        self.inner.set_baudrate(baudrate)?;
+        self.config.baudrate = baudrate;
+        Ok(())
+    }
+}
+
+impl ErrorType for Uart {
+    type Error = Error;
+}
+
+impl Read for Uart {
+    async fn read(&mut self, buf: &mut [u8]) -> Result<usize, Self::Error> {
+        self.inner.read(buf).await.map_err(Error::from)
+    }
+}
+
+impl Write for Uart {
+    async fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
+        self.inner.write(buf).await.map_err(Error::from)
+    }
+
+    /// Waits until the transmit buffer has been handed over to the hardware.
+    ///
+    /// The last bytes may still be in the transmit FIFO or shift register when this returns.
+    async fn flush(&mut self) -> Result<(), Self::Error> {
+        self.inner.flush().await.map_err(Error::from)
+    }
+}
+
+/// Only the baud rate can be changed: line codings with another parity or number of stop bits
+/// than configured, or with other than 8 data bits, are not supported.
+#[cfg(feature = "usb-uart-bridge")]
+impl crate::usb::uart_bridge::BridgedUart for Uart {
+    fn set_line_coding(
+        &mut self,
+        line_coding: &LineCoding,
+    ) -> Result<(), crate::usb::uart_bridge::UnsupportedLineCoding> {
+        use crate::usb::uart_bridge::UnsupportedLineCoding;
+
+        let parity = match line_coding.parity_type() {
+            ParityType::None => Parity::None,
+            ParityType::Even => Parity::Even,
+            ParityType::Odd => Parity::Odd,
+            ParityType::Mark | ParityType::Space => return Err(UnsupportedLineCoding),
+        };
+        let stop_bits = match line_coding.stop_bits() {
+            cdc_acm::StopBits::One => StopBits::One,
+            cdc_acm::StopBits::Two => StopBits::Two,
+            cdc_acm::StopBits::OnePointFive => return Err(UnsupportedLineCoding),
+        };
+        if line_coding.data_bits() != 8
+            || parity != self.config.parity
+            || stop_bits != self.config.stop_bits
+        {
+            return Err(UnsupportedLineCoding);
+        }
+
+        self.set_baudrate(line_coding.data_rate())
+            .map_err(|_| UnsupportedLineCoding)
+    }
+}
@@ -1,14 +1,97 @@
-//! To provide a custom USB configuration, use the `riot_rs::config` attribute macro.
+//! The USB vendor and product ids and strings of the default configuration can be set through
+//! the `CONFIG_USB_VENDOR_ID`, `CONFIG_USB_PRODUCT_ID`, `CONFIG_USB_MANUFACTURER`,
+//! `CONFIG_USB_PRODUCT` and `CONFIG_USB_SERIAL_NUMBER` environment variables.
+//! The product string defaults to the board name.
+//! The ids are parsed as decimal, or as hexadecimal when prefixed with `0x`.
+//! To provide a custom USB configuration, use the `riot_rs::config` attribute macro; it can
+//! start from [`default_config()`].
 
 pub use crate::arch::usb::UsbDriver;
 
+#[cfg(feature = "usb-uart-bridge")]
+pub mod uart_bridge;
+
+#[cfg(context = "riot-rs")]
+const _: () = assert!(
+    crate::arch::capabilities().usb_device,
+    "the MCU has no USB device controller"
+);
+
 pub type UsbBuilder = embassy_usb::Builder<'static, UsbDriver>;
 
 pub type UsbBuilderHook = &'static crate::delegate::Delegate<UsbBuilder>;
 
+// Sizes of the buffers passed to the USB builder, in bytes.
+pub(crate) const DEVICE_DESCRIPTOR_BUF_SIZE: usize = riot_rs_utils::usize_from_env_or!(
+    "CONFIG_USB_DEVICE_DESCRIPTOR_BUF_SIZE",
+    256,
+    "size (in bytes) of the USB device descriptor buffer"
+);
+pub(crate) const CONFIG_DESCRIPTOR_BUF_SIZE: usize = riot_rs_utils::usize_from_env_or!(
+    "CONFIG_USB_CONFIG_DESCRIPTOR_BUF_SIZE",
+    256,
+    "size (in bytes) of the USB configuration descriptor buffer"
+);
+pub(crate) const BOS_DESCRIPTOR_BUF_SIZE: usize = riot_rs_utils::usize_from_env_or!(
+    "CONFIG_USB_BOS_DESCRIPTOR_BUF_SIZE",
+    256,
+    "size (in bytes) of the USB BOS descriptor buffer"
+);
+pub(crate) const MSOS_DESCRIPTOR_BUF_SIZE: usize = riot_rs_utils::usize_from_env_or!(
+    "CONFIG_USB_MSOS_DESCRIPTOR_BUF_SIZE",
+    128,
+    "size (in bytes) of the USB Microsoft OS descriptor buffer"
+);
+pub(crate) const CONTROL_BUF_SIZE: usize = riot_rs_utils::usize_from_env_or!(
+    "CONFIG_USB_CONTROL_BUF_SIZE",
+    128,
+    "size (in bytes) of the USB control transfer buffer"
+);
+
+/// Returns `size` as the maximum packet size of USB full-speed bulk endpoints.
+///
+/// # Panics
+///
+/// Panics if `size` is not 8, 16, 32 or 64, which fails compilation when used in a constant.
+#[cfg(any(feature = "usb-ethernet", feature = "usb-uart-bridge"))]
+pub(crate) const fn bulk_max_packet_size(size: usize) -> u16 {
+    match size {
+        8 | 16 | 32 | 64 => size as u16,
+        _ => panic!("USB full-speed bulk endpoints must have a max packet size of 8, 16, 32 or 64"),
+    }
+}
+
+/// Returns `id` as a USB vendor or product id.
+///
+/// # Panics
+///
+/// Panics if `id` does not fit in 16 bits, which fails compilation when used in a constant.
+const fn usb_id(id: usize) -> u16 {
+    assert!(
+        id <= u16::MAX as usize,
+        "USB vendor and product ids must fit in 16 bits"
+    );
+    id as u16
+}
+
 #[linkme::distributed_slice]
 pub static USB_BUILDER_HOOKS: [UsbBuilderHook] = [..];
 
+/// Creates the USB builder, with the configuration provided by the application, if any.
+pub(crate) fn builder(driver: UsbDriver) -> UsbBuilder {
+    use crate::make_static;
+
+    UsbBuilder::new(
+        driver,
+        config(),
+        &mut make_static!([0; DEVICE_DESCRIPTOR_BUF_SIZE])[..],
+        &mut make_static!([0; CONFIG_DESCRIPTOR_BUF_SIZE])[..],
+        &mut make_static!([0; BOS_DESCRIPTOR_BUF_SIZE])[..],
+        &mut make_static!([0; MSOS_DESCRIPTOR_BUF_SIZE])[..],
+        &mut make_static!([0; CONTROL_BUF_SIZE])[..],
+    )
+}
+
 #[embassy_executor::task]
 pub(crate) async fn usb_task(mut device: embassy_usb::UsbDevice<'static, UsbDriver>) -> ! {
     device.run().await
@@ -16,35 +99,124 @@ pub(crate) async fn usb_task(mut device: embassy_usb::UsbDevice<'static, UsbDriv
 
 #[cfg(feature = "usb-ethernet")]
 pub(crate) mod ethernet {
-    use embassy_usb::class::cdc_ncm::embassy_net::{Device, Runner};
+    use embassy_executor::Spawner;
+    use embassy_usb::class::cdc_ncm::{
+        embassy_net::{Device, Runner, State as NetState},
+        CdcNcmClass, State as CdcNcmState,
+    };
 
-    use crate::{arch::usb::UsbDriver, network::ETHERNET_MTU};
+    use crate::{
+        arch::usb::UsbDriver,
+        make_static,
+        network::ETHERNET_MTU,
+        usb::{bulk_max_packet_size, UsbBuilder},
+    };
 
     pub type NetworkDevice = Device<'static, ETHERNET_MTU>;
 
+    const RX_QUEUE_SIZE: usize = riot_rs_utils::usize_from_env_or!(
+        "CONFIG_USB_ETHERNET_RX_QUEUE_SIZE",
+        4,
+        "number of packets the USB Ethernet receive queue can hold"
+    );
+    const TX_QUEUE_SIZE: usize = riot_rs_utils::usize_from_env_or!(
+        "CONFIG_USB_ETHERNET_TX_QUEUE_SIZE",
+        4,
+        "number of packets the USB Ethernet transmit queue can hold"
+    );
+    const MAX_PACKET_SIZE: u16 = bulk_max_packet_size(riot_rs_utils::usize_from_env_or!(
+        "CONFIG_USB_ETHERNET_MAX_PACKET_SIZE",
+        64,
+        "maximum packet size (in bytes) of the USB Ethernet endpoints"
+    ));
+
+    /// Adds the CDC-NCM class to `builder`, and returns the network device it provides.
+    pub(crate) fn device(builder: &mut UsbBuilder, spawner: Spawner) -> NetworkDevice {
+        // Host's MAC addr. This is the MAC the host "thinks" its USB-to-ethernet adapter has.
+        let host_mac_addr = [0x8A, 0x88, 0x88, 0x88, 0x88, 0x88];
+
+        // Create classes on the builder.
+        let usb_cdc_ecm = CdcNcmClass::new(
+            builder,
+            make_static!(CdcNcmState::new()),
+            host_mac_addr,
+            MAX_PACKET_SIZE,
+        );
+
+        let our_mac_addr = [0xCA, 0xCC, 0xCC, 0xCC, 0xCC, 0xCC];
+
+        let (runner, device) = usb_cdc_ecm
+            .into_embassy_net_device::<ETHERNET_MTU, RX_QUEUE_SIZE, TX_QUEUE_SIZE>(
+                make_static!(NetState::new()),
+                our_mac_addr,
+            );
+
+        spawner.spawn(usb_ncm_task(runner)).unwrap();
+
+        device
+    }
+
     #[embassy_executor::task]
     pub async fn usb_ncm_task(class: Runner<'static, UsbDriver, ETHERNET_MTU>) -> ! {
         class.run().await
     }
 }
 
-pub(crate) fn config() -> embassy_usb::Config<'static> {
+/// USB vendor id of the default configuration.
+pub const VENDOR_ID: u16 = usb_id(riot_rs_utils::usize_from_env_or!(
+    "CONFIG_USB_VENDOR_ID",
+    0xc0de,
+    "USB vendor id"
+));
+/// USB product id of the default configuration.
+pub const PRODUCT_ID: u16 = usb_id(riot_rs_utils::usize_from_env_or!(
+    "CONFIG_USB_PRODUCT_ID",
+    0xcafe,
+    "USB product id"
+));
+/// USB manufacturer string of the default configuration.
+pub const MANUFACTURER: &str =
+    riot_rs_utils::str_from_env_or!("CONFIG_USB_MANUFACTURER", "Embassy", "USB manufacturer");
+/// USB product string of the default configuration.
+///
+/// Defaults to the board name provided by the build system.
+pub const PRODUCT: &str = riot_rs_utils::str_from_env_or!(
+    "CONFIG_USB_PRODUCT",
+    riot_rs_utils::str_from_env_or!(
+        "CONFIG_BOARD",
+        "RIOT-rs device",
+        "board name provided by the build system"
+    ),
+    "USB product"
+);
+/// USB serial number string of the default configuration.
+pub const SERIAL_NUMBER: &str =
+    riot_rs_utils::str_from_env_or!("CONFIG_USB_SERIAL_NUMBER", "12345678", "USB serial number");
+
+/// Returns the default USB configuration.
+///
+/// This is the configuration used unless a custom one is provided with the `riot_rs::config`
+/// attribute macro.
+pub fn default_config() -> embassy_usb::Config<'static> {
+    let mut config = embassy_usb::Config::new(VENDOR_ID, PRODUCT_ID);
+    config.manufacturer = Some(MANUFACTURER);
+    config.product = Some(PRODUCT);
+    config.serial_number = Some(SERIAL_NUMBER);
+    config.max_power = 100;
+    config.max_packet_size_0 = 64;
+
+    // Required for Windows support.
+    config.composite_with_iads = true;
+    config.device_class = 0xEF;
+    config.device_sub_class = 0x02;
+    config.device_protocol = 0x01;
+    config
+}
+
+fn config() -> embassy_usb::Config<'static> {
     #[cfg(not(feature = "override-usb-config"))]
     {
-        // Create embassy-usb Config
-        let mut config = embassy_usb::Config::new(0xc0de, 0xcafe);
-        config.manufacturer = Some("Embassy");
-        config.product = Some("USB-Ethernet example");
-        config.serial_number = Some("12345678");
-        config.max_power = 100;
-        config.max_packet_size_0 = 64;
-
-        // Required for Windows support.
-        config.composite_with_iads = true;
-        config.device_class = 0xEF;
-        config.device_sub_class = 0x02;
-        config.device_protocol = 0x01;
-        config
+        default_config()
     }
     #[cfg(feature = "override-usb-config")]
     {
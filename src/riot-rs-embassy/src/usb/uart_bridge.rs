@@ -0,0 +1,188 @@
+//! Bridges a UART to a USB CDC-ACM interface.
+//!
+//! The bridge shows up as a serial port on the USB host.
+//! Once the host has opened that serial port (by setting DTR), data is forwarded in both
+//! directions between it and the UART, and the line coding (baud rate, parity, stop bits)
+//! requested by the host is applied to the UART.
+//!
+//! Flow control works as follows:
+//! - The next USB packet is only read once the previous one has been written to the UART, so
+//!   the host is throttled when the UART cannot keep up.
+//! - The UART is not read while the host has RTS cleared. Received data then waits in the UART
+//!   receive buffer; if the UART uses hardware flow control, the remote device is throttled in
+//!   turn.
+//!
+//! The maximum packet size of the CDC-ACM endpoints can be set with the
+//! `CONFIG_USB_UART_BRIDGE_MAX_PACKET_SIZE` environment variable.
+//!
+//! Any UART implementing [`BridgedUart`] can be bridged, including the portable
+//! `uart::Uart` when the `uart` feature is enabled.
+//!
+//! Example:
+//! ```Rust
+//! #[riot_rs::task(autostart, peripherals, usb_builder_hook)]
+//! async fn bridge(peripherals: UartPeripherals) {
+//!     let config = uart::Config::default();
+//!     let uart = arch::uart::new(peripherals.uart, peripherals.rx, peripherals.tx, &config).unwrap();
+//!     let bridge = USB_BUILDER_HOOK.with(|builder| UartBridge::new(builder)).await;
+//!     bridge.run(uart).await
+//! }
+//! ```
+//!
+//! See the `usb-uart-bridge` example for a complete application.
+
+use embassy_futures::select::{select3, Either3};
+use embassy_time::{Duration, Timer};
+use embassy_usb::{
+    class::cdc_acm::{CdcAcmClass, LineCoding, State},
+    driver::EndpointError,
+};
+use embedded_io_async::{Read, Write};
+
+use crate::{
+    make_static,
+    usb::{bulk_max_packet_size, UsbBuilder, UsbDriver},
+};
+
+const MAX_PACKET_SIZE: u16 = bulk_max_packet_size(riot_rs_utils::usize_from_env_or!(
+    "CONFIG_USB_UART_BRIDGE_MAX_PACKET_SIZE",
+    64,
+    "maximum packet size (in bytes) of the USB-to-UART bridge endpoints"
+));
+
+// The control lines and the line coding can only be polled.
+const CONTROL_POLL_INTERVAL: Duration = Duration::from_millis(10);
+// Pause after a UART error, so that a persistent error does not keep the executor busy.
+const UART_ERROR_BACKOFF: Duration = Duration::from_millis(100);
+
+/// UART that can be driven by a [`UartBridge`].
+///
+/// Reads must not lose data when they are cancelled, as the bridge cancels pending UART reads
+/// when it has something else to do.
+pub trait BridgedUart: Read + Write {
+    /// Reconfigures the UART for the line coding requested by the USB host.
+    ///
+    /// The UART must keep its previous configuration when this fails.
+    fn set_line_coding(&mut self, line_coding: &LineCoding) -> Result<(), UnsupportedLineCoding>;
+}
+
+/// The UART does not support the line coding requested by the USB host.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct UnsupportedLineCoding;
+
+#[derive(Debug)]
+enum Error {
+    /// The USB device got disconnected or reset.
+    Usb,
+    /// The UART failed to transfer data.
+    Uart,
+}
+
+impl From<EndpointError> for Error {
+    fn from(_err: EndpointError) -> Self {
+        Self::Usb
+    }
+}
+
+/// USB-to-UART bridge.
+///
+/// See the [module documentation](self) for how to use it.
+pub struct UartBridge {
+    class: CdcAcmClass<'static, UsbDriver>,
+    line_coding: LineCoding,
+}
+
+impl UartBridge {
+    /// Adds the CDC-ACM interface of the bridge to the USB device being built.
+    ///
+    /// # Panics
+    ///
+    /// Panics if called more than once.
+    pub fn new(builder: &mut UsbBuilder) -> Self {
+        let state = make_static!(State::new());
+        let class = CdcAcmClass::new(builder, state, MAX_PACKET_SIZE);
+        let line_coding = class.line_coding();
+        Self { class, line_coding }
+    }
+
+    /// Forwards data between the USB host and `uart`.
+    ///
+    /// Line codings that the UART does not support are not applied; the UART then keeps its
+    /// previous configuration, as CDC-ACM has no way to reject them.
+    /// Data affected by a UART error is lost, and forwarding resumes after a short pause.
+    pub async fn run<U: BridgedUart>(mut self, mut uart: U) -> ! {
+        let mut usb_buf = [0; MAX_PACKET_SIZE as usize];
+        let mut uart_buf = [0; MAX_PACKET_SIZE as usize];
+
+        loop {
+            self.class.wait_connection().await;
+
+            match self.forward(&mut uart, &mut usb_buf, &mut uart_buf).await {
+                // Wait for the host to configure the device again.
+                Err(Error::Usb) => {}
+                Err(Error::Uart) => Timer::after(UART_ERROR_BACKOFF).await,
+                Ok(()) => unreachable!(),
+            }
+        }
+    }
+
+    async fn forward<U: BridgedUart>(
+        &mut self,
+        uart: &mut U,
+        usb_buf: &mut [u8],
+        uart_buf: &mut [u8],
+    ) -> Result<(), Error> {
+        loop {
+            if !self.class.dtr() {
+                Timer::after(CONTROL_POLL_INTERVAL).await;
+                continue;
+            }
+
+            self.apply_line_coding(uart);
+
+            let host_ready = self.class.rts();
+            let uart_read = async {
+                if host_ready {
+                    uart.read(uart_buf).await
+                } else {
+                    core::future::pending().await
+                }
+            };
+            let event = select3(
+                self.class.read_packet(usb_buf),
+                uart_read,
+                Timer::after(CONTROL_POLL_INTERVAL),
+            )
+            .await;
+
+            match event {
+                Either3::First(len) => {
+                    let (packet, _) = usb_buf.split_at(len?);
+                    uart.write_all(packet).await.map_err(|_| Error::Uart)?;
+                }
+                Either3::Second(len) => {
+                    let (data, _) = uart_buf.split_at(len.map_err(|_| Error::Uart)?);
+                    self.class.write_packet(data).await?;
+                }
+                // Check the control lines and the line coding again.
+                Either3::Third(()) => {}
+            }
+        }
+    }
+
+    fn apply_line_coding<U: BridgedUart>(&mut self, uart: &mut U) {
+        let requested = self.class.line_coding();
+        let applied = &self.line_coding;
+
+        let changed = requested.data_rate() != applied.data_rate()
+            || requested.data_bits() != applied.data_bits()
+            || requested.parity_type() != applied.parity_type()
+            || requested.stop_bits() != applied.stop_bits();
+
+        // An unsupported line coding is only attempted once, until the host requests another one.
+        if changed {
+            let _ = uart.set_line_coding(&requested);
+            self.line_coding = requested;
+        }
+    }
+}
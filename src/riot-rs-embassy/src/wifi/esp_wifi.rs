@@ -1,7 +1,11 @@
 use esp_wifi::{wifi::WifiStaDevice, EspWifiInitialization};
 use once_cell::sync::OnceCell;
 
-use crate::{arch::OptionalPeripherals, Spawner};
+use crate::{
+    arch::OptionalPeripherals,
+    define_peripherals::{take, with_taker},
+    Spawner,
+};
 
 use esp_wifi::wifi::{WifiController, WifiDevice};
 
@@ -15,7 +19,7 @@ pub type NetworkDevice = WifiDevice<'static, WifiStaDevice>;
 pub static WIFI_INIT: OnceCell<EspWifiInitialization> = OnceCell::new();
 
 pub fn init(peripherals: &mut OptionalPeripherals, spawner: Spawner) -> NetworkDevice {
-    let wifi = peripherals.WIFI.take().unwrap();
+    let wifi = with_taker(module_path!(), || take(&mut peripherals.WIFI, "WIFI"));
     let init = WIFI_INIT.get().unwrap();
     let (device, controller) = esp_wifi::wifi::new_with_mode(init, wifi, WifiStaDevice).unwrap();
 
@@ -1,8 +1,9 @@
 /// Registers the function this attribute macro is applied on to provide the configuration for the
 /// associated driver during initial system configuration.
 ///
-/// **Important**: for this configuration to be taken into account, a specific Cargo feature may
-/// need to be enabled on the `riot-rs` dependency, for each configuration type (see table below).
+/// **Important**: for this configuration to be taken into account, a specific Cargo feature needs
+/// to be enabled on the `riot-rs` dependency, for each configuration type (see table below).
+/// Compilation fails if it is not.
 ///
 /// The name of the function does not matter as it will be renamed by the macro.
 ///
@@ -58,23 +59,32 @@ pub fn config(args: TokenStream, item: TokenStream) -> TokenStream {
 
     let riot_rs_crate = utils::riot_rs_crate();
 
-    let (config_fn_name, return_type) = match attrs.kind {
-        Some(ConfigKind::Network) => (
+    let Some(kind) = attrs.kind else {
+        panic!("a configuration kind must be specified");
+    };
+
+    let (config_fn_name, return_type) = match kind {
+        ConfigKind::Network => (
             format_ident!("riot_rs_network_config"),
             quote! {#riot_rs_crate::embassy::embassy_net::Config},
         ),
-        Some(ConfigKind::Usb) => (
+        ConfigKind::Usb => (
             format_ident!("riot_rs_usb_config"),
             quote! {#riot_rs_crate::embassy::embassy_usb::Config<'static>},
         ),
-        None => {
-            panic!("a configuration kind must be specified");
-        }
     };
 
+    let requirement_checks = utils::requirement_checks(
+        &riot_rs_crate,
+        &format!("`{}` configuration", kind.as_name()),
+        &[utils::Requirement::Feature(kind.required_feature())],
+    );
+
     // Place the provided function into another function whose type signature we enforce.
     // This is important as that function will be called unsafely via FFI.
     let expanded = quote! {
+        #requirement_checks
+
         #[no_mangle]
         fn #config_fn_name() -> #return_type {
             #[inline(always)]
@@ -145,5 +155,13 @@ mod config_macro {
                 Self::Usb => "usb",
             }
         }
+
+        /// Returns the Cargo feature of `riot-rs` for this configuration to be taken into account.
+        pub fn required_feature(&self) -> &'static str {
+            match self {
+                Self::Network => "override-network-config",
+                Self::Usb => "override-usb-config",
+            }
+        }
     }
 }
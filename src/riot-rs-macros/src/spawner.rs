@@ -68,7 +68,13 @@ pub fn spawner(args: TokenStream, item: TokenStream) -> TokenStream {
     let new_function_name = format_ident!("__start_{spawner_function_name}");
 
     let peripheral_param = if attrs.peripherals {
-        quote! {, peripherals.take_peripherals()}
+        quote! {
+            ,
+            #riot_rs_crate::define_peripherals::with_taker(
+                stringify!(#spawner_function_name),
+                || peripherals.take_peripherals(),
+            )
+        }
     } else {
         quote! {}
     };
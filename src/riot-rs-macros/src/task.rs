@@ -83,7 +83,12 @@ pub fn task(args: TokenStream, item: TokenStream) -> TokenStream {
 
     let expanded = if attrs.autostart {
         let peripheral_param = if attrs.peripherals {
-            quote! {peripherals.take_peripherals()}
+            quote! {
+                #riot_rs_crate::define_peripherals::with_taker(
+                    stringify!(#task_function_name),
+                    || peripherals.take_peripherals(),
+                )
+            }
         } else {
             quote! {}
         };
@@ -189,6 +194,26 @@ mod task {
             }
         }
 
+        /// Returns the Cargo features of `riot-rs` and the board capabilities the hook relies on.
+        pub fn requirements(&self) -> &'static [crate::utils::Requirement<'static>] {
+            use crate::utils::Requirement;
+
+            match self {
+                Self::UsbBuilder => &[
+                    Requirement::Feature("usb"),
+                    Requirement::Capability("hw/usb-device-port"),
+                ],
+            }
+        }
+
+        /// Returns the field of `arch::capabilities()` indicating whether the MCU supports the
+        /// hook, along with a description of what it requires.
+        pub fn required_mcu_capability(&self) -> (&'static str, &'static str) {
+            match self {
+                Self::UsbBuilder => ("usb_device", "a USB device controller"),
+            }
+        }
+
         pub fn delegate_ident(&self) -> String {
             self.param_name().to_uppercase()
         }
@@ -246,8 +271,28 @@ mod task {
             let delegate_hook_ident = format_ident!("{delegate_ident}");
             let delegate_hook_ref_ident = format_ident!("{delegate_ident}_REF");
 
+            let param_name = kind.param_name();
+            let requirement_checks = crate::utils::requirement_checks(
+                riot_rs_crate,
+                &format!("the `{param_name}` hook"),
+                kind.requirements(),
+            );
+            let (mcu_capability, mcu_requirement) = kind.required_mcu_capability();
+            let mcu_capability = format_ident!("{mcu_capability}");
+            let missing_mcu_capability_msg =
+                format!("the `{param_name}` hook requires an MCU with {mcu_requirement}");
+
             // TODO: try to reduce namespace pollution
             quote! {
+                #requirement_checks
+
+                // The capabilities are only meaningful when building for an actual MCU.
+                const _: () = assert!(
+                    !#riot_rs_crate::buildinfo::has_context("riot-rs")
+                        || #riot_rs_crate::embassy::arch::capabilities().#mcu_capability,
+                    #missing_mcu_capability_msg,
+                );
+
                 static #delegate_hook_ident: #delegate_type<#delegate_inner_type> = #delegate_type::new();
 
                 #[#riot_rs_crate::embassy::distributed_slice(#distributed_slice_type)]
@@ -39,7 +39,7 @@ pub fn thread(args: TokenStream, item: TokenStream) -> TokenStream {
 
     use quote::quote;
 
-    use crate::utils::find_crate;
+    use crate::utils::{find_crate, requirement_checks, Requirement};
 
     let mut attrs = Attributes::default();
     let thread_parser = syn::meta::parser(|meta| attrs.parse(&meta));
@@ -64,15 +64,24 @@ pub fn thread(args: TokenStream, item: TokenStream) -> TokenStream {
         priority,
     } = Parameters::from(attrs);
 
-    let thread_crate = {
+    let (thread_crate, requirement_checks) = {
         match (find_crate("riot-rs"), find_crate("riot-rs-threads")) {
-            (Some(riot_rs), _) => quote! { #riot_rs::thread },
-            (None, Some(riot_rs_threads)) => quote! { #riot_rs_threads },
+            (Some(riot_rs), _) => (
+                quote! { #riot_rs::thread },
+                requirement_checks(
+                    &riot_rs,
+                    "the `thread` macro",
+                    &[Requirement::Feature("threading")],
+                ),
+            ),
+            (None, Some(riot_rs_threads)) => (quote! { #riot_rs_threads }, quote! {}),
             _ => panic!(r#"neither "riot-rs" nor "riot-rs-threads" found in dependencies!"#),
         }
     };
 
     let expanded = quote! {
+        #requirement_checks
+
         #no_mangle_attr
         #thread_function
 
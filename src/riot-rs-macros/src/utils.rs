@@ -31,3 +31,47 @@ pub fn find_crate(name: &str) -> Option<syn::Ident> {
         None
     }
 }
+
+/// A build requirement checked against `riot_rs::buildinfo` by the generated code.
+pub enum Requirement<'a> {
+    /// A Cargo feature of `riot-rs`.
+    Feature(&'a str),
+    /// A laze capability of the board (e.g., `hw/usb-device-port`).
+    Capability(&'a str),
+}
+
+/// Returns compile-time assertions checking `requirements`, failing with a message naming `user`.
+///
+/// Capabilities are only checked when building for an actual board, i.e., within the `riot-rs`
+/// laze context.
+pub fn requirement_checks(
+    riot_rs_crate: &syn::Ident,
+    user: &str,
+    requirements: &[Requirement],
+) -> proc_macro2::TokenStream {
+    use quote::quote;
+
+    let checks = requirements.iter().map(|requirement| match requirement {
+        Requirement::Feature(feature) => {
+            let msg = format!("{user} requires the `{feature}` feature of `riot-rs`");
+            quote! {
+                const _: () = assert!(
+                    #riot_rs_crate::buildinfo::has_feature(#feature),
+                    #msg,
+                );
+            }
+        }
+        Requirement::Capability(capability) => {
+            let msg = format!("{user} requires a board with the `{capability}` capability");
+            quote! {
+                const _: () = assert!(
+                    !#riot_rs_crate::buildinfo::has_context("riot-rs")
+                        || #riot_rs_crate::buildinfo::has_capability(#capability),
+                    #msg,
+                );
+            }
+        }
+    });
+
+    quote! {#(#checks)*}
+}
@@ -68,6 +68,11 @@ unsafe fn HardFault(ef: &ExceptionFrame) -> ! {
 
     let xpsr = ef.xpsr();
 
+    #[cfg(feature = "isr-stack-canary")]
+    if !crate::isr_stack::is_intact() {
+        riot_rs_debug::println!("ISR stack overflow detected");
+    }
+
     let ici_it = (((xpsr >> 25) & 0x3) << 6) | ((xpsr >> 10) & 0x3f);
     let thumb_bit = ((xpsr >> 24) & 0x1) == 1;
     let exception_number = (xpsr & 0x1ff) as usize;
@@ -0,0 +1,70 @@
+//! Overflow detection for the ISR stack.
+//!
+//! The ISR stack is used by interrupt handlers, and thus by the interrupt executor, and also
+//! serves as the main stack before threading starts.
+//! Canary words are written at its lowest addresses during startup, so that an overflow can be
+//! detected by [`check()`] once it has overwritten them.
+//!
+//! Detection is best-effort: an overflow that skips over the canary words goes unnoticed, and
+//! memory below the stack is already corrupted by the time the overflow is detected.
+
+/// Value written to each canary word.
+const CANARY: u32 = 0xDEAD_BEEF;
+
+/// Number of canary words at the bottom of the stack.
+const CANARY_WORDS: usize = 4;
+
+const _: () = assert!(
+    crate::ISR_STACKSIZE >= CANARY_WORDS * core::mem::size_of::<u32>(),
+    "the ISR stack is too small to hold the canary words"
+);
+
+cfg_if::cfg_if! {
+    if #[cfg(context = "cortex-m")] {
+        fn canary_ptr() -> *mut u32 {
+            extern "C" {
+                // Defined in `isr_stack.ld.in`.
+                static mut _stack_bottom: u32;
+            }
+            // SAFETY: only the address of the symbol is taken.
+            unsafe { core::ptr::addr_of_mut!(_stack_bottom) }
+        }
+    } else if #[cfg(context = "riot-rs")] {
+        compile_error!("ISR stack canaries are not supported on this architecture");
+    } else {
+        // Provide a default, for arch-independent tooling
+        fn canary_ptr() -> *mut u32 {
+            unimplemented!()
+        }
+    }
+}
+
+/// Writes the canary words.
+///
+/// Must be called before the stack could grow down to the canary words.
+pub(crate) fn init() {
+    let ptr = canary_ptr();
+    for i in 0..CANARY_WORDS {
+        // SAFETY: the canary words are at the bottom of the ISR stack, which is reserved by the
+        // linker script and at least `CANARY_WORDS` words large.
+        unsafe { ptr.add(i).write_volatile(CANARY) };
+    }
+}
+
+/// Returns whether the canary words are intact, i.e., no overflow of the ISR stack was detected.
+pub fn is_intact() -> bool {
+    let ptr = canary_ptr();
+    // SAFETY: see `init()`.
+    (0..CANARY_WORDS).all(|i| unsafe { ptr.add(i).read_volatile() } == CANARY)
+}
+
+/// Checks the canary words.
+///
+/// # Panics
+///
+/// Panics if an overflow of the ISR stack was detected.
+pub fn check() {
+    if !is_intact() {
+        panic!("ISR stack overflow detected");
+    }
+}
@@ -13,6 +13,8 @@
 #![reexport_test_harness_main = "test_main"]
 pub mod testing;
 
+#[cfg(feature = "isr-stack-canary")]
+pub mod isr_stack;
 #[cfg(feature = "threading")]
 mod threading;
 
@@ -68,6 +70,9 @@ pub static INIT_FUNCS: [fn()] = [..];
 fn startup() -> ! {
     arch::init();
 
+    #[cfg(feature = "isr-stack-canary")]
+    isr_stack::init();
+
     #[cfg(feature = "debug-console")]
     riot_rs_debug::init();
 
@@ -95,4 +95,47 @@ mod tests {
         runqueue.advance(RunqueueId::new(0));
         assert_eq!(runqueue.get_next(), Some(ThreadId::new(0)));
     }
+
+    #[test]
+    fn test_rq_remove() {
+        let mut runqueue: RunQueue<8, 32> = RunQueue::new();
+
+        runqueue.add(ThreadId::new(0), RunqueueId::new(0));
+        runqueue.add(ThreadId::new(1), RunqueueId::new(0));
+        runqueue.add(ThreadId::new(2), RunqueueId::new(0));
+        runqueue.add(ThreadId::new(3), RunqueueId::new(1));
+
+        // Removing a thread that is not the head.
+        runqueue.remove(ThreadId::new(1), RunqueueId::new(0));
+        runqueue.del(ThreadId::new(3), RunqueueId::new(1));
+        assert_eq!(runqueue.get_next(), Some(ThreadId::new(0)));
+        runqueue.advance(RunqueueId::new(0));
+        assert_eq!(runqueue.get_next(), Some(ThreadId::new(2)));
+        runqueue.advance(RunqueueId::new(0));
+        assert_eq!(runqueue.get_next(), Some(ThreadId::new(0)));
+
+        // Removing a thread that is not in the runqueue.
+        runqueue.remove(ThreadId::new(1), RunqueueId::new(0));
+        assert_eq!(runqueue.get_next(), Some(ThreadId::new(0)));
+
+        // Removing the last threads of a runqueue.
+        runqueue.remove(ThreadId::new(0), RunqueueId::new(0));
+        assert_eq!(runqueue.get_next(), Some(ThreadId::new(2)));
+        runqueue.remove(ThreadId::new(2), RunqueueId::new(0));
+        assert_eq!(runqueue.get_next(), None);
+    }
+
+    #[test]
+    fn test_rq_remove_keeps_higher_prio() {
+        let mut runqueue: RunQueue<8, 32> = RunQueue::new();
+
+        runqueue.add(ThreadId::new(0), RunqueueId::new(0));
+        runqueue.add(ThreadId::new(1), RunqueueId::new(2));
+        runqueue.add(ThreadId::new(2), RunqueueId::new(2));
+
+        runqueue.remove(ThreadId::new(2), RunqueueId::new(2));
+        assert_eq!(runqueue.get_next(), Some(ThreadId::new(1)));
+        runqueue.remove(ThreadId::new(1), RunqueueId::new(2));
+        assert_eq!(runqueue.get_next(), Some(ThreadId::new(0)));
+    }
 }
@@ -96,6 +96,19 @@ impl<const N_QUEUES: usize, const N_THREADS: usize> RunQueue<{ N_QUEUES }, { N_T
         }
     }
 
+    /// Removes thread with pid `n` from runqueue number `rq`.
+    ///
+    /// Unlike [`RunQueue::del()`], `n` does not need to be the queue's head.
+    /// Does nothing if `n` is not in the queue.
+    pub fn remove(&mut self, n: ThreadId, rq: RunqueueId) {
+        debug_assert!(usize::from(n) < N_THREADS);
+        debug_assert!(usize::from(rq) < N_QUEUES);
+        self.queues.remove(n.0, rq.0);
+        if self.queues.is_empty(rq.0) {
+            self.bitcache &= !(1 << rq.0);
+        }
+    }
+
     fn ffs(val: usize) -> u32 {
         (USIZE_BITS as u32 - val.leading_zeros()) as u32
     }
@@ -196,6 +209,33 @@ mod clist {
             }
         }
 
+        pub fn remove(&mut self, n: u8, rq: u8) {
+            let Some(head) = self.peek_head(rq) else {
+                return;
+            };
+            if head == n {
+                self.pop_head(rq);
+                return;
+            }
+            let mut prev = head;
+            loop {
+                let next = self.next_idxs[prev as usize];
+                if next == head {
+                    // went around the whole list, `n` is not in it
+                    return;
+                }
+                if next == n {
+                    self.next_idxs[prev as usize] = self.next_idxs[n as usize];
+                    if self.tail[rq as usize] == n {
+                        self.tail[rq as usize] = prev;
+                    }
+                    self.next_idxs[n as usize] = Self::sentinel();
+                    return;
+                }
+                prev = next;
+            }
+        }
+
         pub fn peek_head(&self, rq: u8) -> Option<u8> {
             if self.tail[rq as usize] == Self::sentinel() {
                 None
@@ -277,6 +317,25 @@ mod clist {
             assert!(clist.is_empty(0));
         }
 
+        #[test]
+        fn test_clist_remove() {
+            let mut clist: CList<8, 32> = CList::new();
+            clist.push(0, 0);
+            clist.push(1, 0);
+            clist.push(2, 0);
+            clist.remove(1, 0);
+            clist.remove(5, 0);
+            assert_eq!(clist.peek_head(0), Some(0));
+            clist.remove(2, 0);
+            clist.push(3, 0);
+            assert_eq!(clist.pop_head(0), Some(0));
+            assert_eq!(clist.pop_head(0), Some(3));
+            assert_eq!(clist.pop_head(0), None);
+            clist.push(1, 0);
+            clist.remove(1, 0);
+            assert!(clist.is_empty(0));
+        }
+
         #[test]
         fn test_clist_peek_head() {
             let mut clist: CList<8, 32> = CList::new();
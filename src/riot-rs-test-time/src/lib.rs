@@ -0,0 +1,227 @@
+//! Simulated clock for host tests of code using `embassy-time`.
+//!
+//! [`run()`] runs a future on an executor using `embassy-time`'s [`MockDriver`] as time driver,
+//! and advances the simulated time in fixed steps whenever the future is pending.
+//! Timers, timeouts and delays thus complete instantly, while still being observed at the
+//! expected [`Instant`]s, which makes debouncing, scheduling or retry logic testable
+//! deterministically.
+//!
+//! The future is polled after each step even if it has not been woken up, so mocks of
+//! peripherals can simply be polled for their state, without registering wakers.
+//!
+//! This crate provides the timer queue through `embassy-executor`'s `integrated-timers` feature,
+//! so crates using it must not enable `embassy-time`'s `generic-queue` feature.
+//! It also runs its own executor, and thus defines the `__pender` function of
+//! `embassy-executor`: test binaries using it cannot contain another `embassy-executor` executor
+//! (e.g., through an `arch-*` feature), as the `__pender` symbols would clash.
+//! All simulations of a process share that executor.
+//!
+//! Example:
+//! ```Rust
+//! let elapsed = riot_rs_test_time::run(Duration::from_millis(1), Duration::from_secs(10), async {
+//!     let start = Instant::now();
+//!     Timer::after_secs(5).await;
+//!     start.elapsed()
+//! });
+//! assert_eq!(elapsed, Duration::from_secs(5));
+//! ```
+
+use std::{
+    cell::{Cell, RefCell},
+    future::{poll_fn, Future},
+    pin::Pin,
+    ptr,
+    rc::Rc,
+    sync::{Mutex, OnceLock, PoisonError},
+    task::{Poll, Waker},
+};
+
+use embassy_executor::raw::{Executor, TaskStorage};
+use embassy_time::{Duration, Instant, MockDriver};
+
+// The time driver is global, so simulations must not run concurrently.
+static CLOCK_LOCK: Mutex<()> = Mutex::new(());
+
+static SIMULATOR: OnceLock<Simulator> = OnceLock::new();
+
+type LocalBoxFuture = Pin<Box<dyn Future<Output = ()>>>;
+
+/// Executor shared by all simulations, running a single task that polls the future of the
+/// current simulation.
+///
+/// Embassy executors and tasks must be `'static` and cannot be freed, hence the reuse.
+struct Simulator {
+    executor: Executor,
+    task: TaskStorage<LocalBoxFuture>,
+    started: Cell<bool>,
+    future: RefCell<Option<LocalBoxFuture>>,
+    waker: RefCell<Option<Waker>>,
+}
+
+// SAFETY: the simulator is only accessed while holding `CLOCK_LOCK`, so never concurrently, and
+// the future of a simulation is dropped before the lock is released.
+unsafe impl Send for Simulator {}
+// SAFETY: see above.
+unsafe impl Sync for Simulator {}
+
+impl Simulator {
+    fn new() -> Self {
+        Self {
+            executor: Executor::new(ptr::null_mut()),
+            task: TaskStorage::new(),
+            started: Cell::new(false),
+            future: RefCell::new(None),
+            waker: RefCell::new(None),
+        }
+    }
+
+    async fn poll_simulations(&self) {
+        poll_fn(|cx| {
+            *self.waker.borrow_mut() = Some(cx.waker().clone());
+            let mut future = self.future.borrow_mut();
+            if let Some(simulation) = &mut *future {
+                if simulation.as_mut().poll(cx).is_ready() {
+                    *future = None;
+                }
+            }
+            Poll::<()>::Pending
+        })
+        .await
+    }
+}
+
+/// Drops the future of the current simulation, even if it panicked.
+struct SimulationGuard(&'static Simulator);
+
+impl Drop for SimulationGuard {
+    fn drop(&mut self) {
+        self.0.future.take();
+    }
+}
+
+/// Runs `future` to completion, advancing the simulated time by `step` whenever it is pending.
+///
+/// Simulations are serialized, so that concurrent tests do not advance the time of each other.
+/// The simulated time is not reset between simulations: use [`Instant::now()`] within `future`
+/// to measure the time it takes.
+///
+/// # Panics
+///
+/// Panics if `future` does not complete within `timeout` of simulated time.
+pub fn run<F>(step: Duration, timeout: Duration, future: F) -> F::Output
+where
+    F: Future + 'static,
+    F::Output: 'static,
+{
+    let _lock = CLOCK_LOCK.lock().unwrap_or_else(PoisonError::into_inner);
+
+    let simulator = SIMULATOR.get_or_init(Simulator::new);
+    if !simulator.started.replace(true) {
+        let token = simulator
+            .task
+            .spawn(|| Box::pin(simulator.poll_simulations()));
+        simulator.executor.spawner().must_spawn(token);
+    }
+
+    let output = Rc::new(Cell::new(None));
+    let _guard = SimulationGuard(simulator);
+    *simulator.future.borrow_mut() = Some(Box::pin({
+        let output = output.clone();
+        async move { output.set(Some(future.await)) }
+    }));
+
+    let start = Instant::now();
+    loop {
+        if let Some(waker) = &*simulator.waker.borrow() {
+            waker.wake_by_ref();
+        }
+        // SAFETY: the executor is only used while holding `CLOCK_LOCK`, and not polled
+        // reentrantly.
+        unsafe { simulator.executor.poll() };
+        if let Some(result) = output.take() {
+            return result;
+        }
+
+        assert!(
+            start.elapsed() < timeout,
+            "future did not complete within {} ms of simulated time",
+            timeout.as_millis()
+        );
+        // This runs the timers that expire within the step.
+        MockDriver::get().advance(step);
+    }
+}
+
+// Called by the executor when a task is woken up; tasks are polled at every step anyway.
+#[export_name = "__pender"]
+fn pender(_context: *mut ()) {}
+
+#[cfg(test)]
+mod tests {
+    use embassy_time::{with_timeout, Timer};
+
+    use super::*;
+
+    const STEP: Duration = Duration::from_millis(1);
+
+    #[test]
+    fn test_timer() {
+        let elapsed = run(STEP, Duration::from_secs(10), async {
+            let start = Instant::now();
+            Timer::after_secs(5).await;
+            Timer::after_millis(20).await;
+            start.elapsed()
+        });
+        assert_eq!(elapsed, Duration::from_millis(5020));
+    }
+
+    #[test]
+    fn test_timeout() {
+        let (result, elapsed) = run(STEP, Duration::from_secs(10), async {
+            let start = Instant::now();
+            let result = with_timeout(Duration::from_secs(1), Timer::after_secs(5)).await;
+            (result, start.elapsed())
+        });
+        assert!(result.is_err());
+        assert_eq!(elapsed, Duration::from_secs(1));
+    }
+
+    #[test]
+    fn test_polled_at_every_step() {
+        let polls = run(STEP, Duration::from_secs(1), async {
+            let mut polls = 0;
+            poll_fn(|_| {
+                polls += 1;
+                if polls == 100 {
+                    std::task::Poll::Ready(())
+                } else {
+                    std::task::Poll::Pending
+                }
+            })
+            .await;
+            polls
+        });
+        assert_eq!(polls, 100);
+    }
+
+    #[test]
+    #[should_panic(expected = "future did not complete")]
+    fn test_timeout_panics() {
+        run(STEP, Duration::from_millis(100), Timer::after_secs(1));
+    }
+
+    #[test]
+    fn test_run_after_timeout() {
+        let result = std::panic::catch_unwind(|| {
+            run(STEP, Duration::from_millis(100), Timer::after_secs(1));
+        });
+        assert!(result.is_err());
+
+        let elapsed = run(STEP, Duration::from_secs(1), async {
+            let start = Instant::now();
+            Timer::after_millis(20).await;
+            start.elapsed()
+        });
+        assert_eq!(elapsed, Duration::from_millis(20));
+    }
+}
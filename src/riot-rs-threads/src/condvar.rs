@@ -0,0 +1,123 @@
+//! This module provides a condition variable.
+use core::cell::UnsafeCell;
+
+use crate::{mutex::MutexGuard, threadlist::ThreadList, ThreadState};
+
+/// A condition variable, to block threads until some condition protected by a
+/// [`Mutex`](crate::mutex::Mutex) becomes true.
+///
+/// Notifying can be done from ISRs.
+///
+/// [`Condvar::notify_one()`] wakes up the highest-priority waiter.
+pub struct Condvar {
+    waiters: UnsafeCell<ThreadList>,
+}
+
+unsafe impl Sync for Condvar {}
+
+impl Condvar {
+    /// Creates a new [`Condvar`].
+    pub const fn new() -> Self {
+        Self {
+            waiters: UnsafeCell::new(ThreadList::new()),
+        }
+    }
+
+    /// Blocks the current thread until this condition variable is notified.
+    ///
+    /// The mutex guarded by `guard` is atomically unlocked while waiting, and locked again
+    /// before returning.
+    /// As with any condition variable, spurious wakeups are possible in the sense that the
+    /// condition may not hold anymore once the mutex is re-acquired, so this is meant to be
+    /// called in a loop checking the condition.
+    ///
+    /// # Panics
+    ///
+    /// Panics if this is called outside of a thread context.
+    pub fn wait<'a, T>(&self, guard: MutexGuard<'a, T>) -> MutexGuard<'a, T> {
+        let mutex = critical_section::with(|cs| {
+            let mutex = guard.into_mutex();
+            let waiters = unsafe { &mut *self.waiters.get() };
+            waiters.put_current(cs, ThreadState::CondvarBlocked);
+            mutex
+        });
+        mutex.lock()
+    }
+
+    /// Wakes up the highest-priority thread blocked on this condition variable.
+    ///
+    /// Returns `false` if no thread was waiting.
+    pub fn notify_one(&self) -> bool {
+        critical_section::with(|cs| {
+            let waiters = unsafe { &mut *self.waiters.get() };
+            waiters.pop(cs).is_some()
+        })
+    }
+
+    /// Wakes up all threads blocked on this condition variable.
+    pub fn notify_all(&self) {
+        critical_section::with(|cs| {
+            let waiters = unsafe { &mut *self.waiters.get() };
+            while waiters.pop(cs).is_some() {}
+        })
+    }
+}
+
+impl Default for Condvar {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        mutex::Mutex,
+        testing::{set_current, setup, state},
+    };
+
+    /// Makes threads `thread_ids` wait on `condvar`, in order.
+    fn wait_all(condvar: &Condvar, mutex: &Mutex<()>, thread_ids: &[u8]) {
+        for &thread_id in thread_ids {
+            set_current(thread_id);
+            // Waiting never blocks in tests, and the mutex is unlocked while waiting, so it
+            // is locked again right away.
+            drop(condvar.wait(mutex.lock()));
+            assert_eq!(state(thread_id), ThreadState::CondvarBlocked);
+            assert!(!mutex.is_locked());
+        }
+        set_current(0);
+    }
+
+    #[test]
+    fn test_notify_one() {
+        let _lock = setup(&[1, 2, 3]);
+        let condvar = Condvar::new();
+        let mutex = Mutex::new(());
+
+        assert!(!condvar.notify_one());
+
+        wait_all(&condvar, &mutex, &[1, 2]);
+        assert!(condvar.notify_one());
+        assert_eq!(state(2), ThreadState::Running);
+        assert_eq!(state(1), ThreadState::CondvarBlocked);
+
+        assert!(condvar.notify_one());
+        assert_eq!(state(1), ThreadState::Running);
+        assert!(!condvar.notify_one());
+    }
+
+    #[test]
+    fn test_notify_all() {
+        let _lock = setup(&[1, 2, 3]);
+        let condvar = Condvar::new();
+        let mutex = Mutex::new(());
+
+        wait_all(&condvar, &mutex, &[1, 2]);
+        condvar.notify_all();
+        assert_eq!(state(1), ThreadState::Running);
+        assert_eq!(state(2), ThreadState::Running);
+        assert!(!condvar.notify_one());
+    }
+}
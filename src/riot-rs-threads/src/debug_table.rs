@@ -0,0 +1,66 @@
+//! Describes the memory layout of the thread table for external debuggers.
+//!
+//! probe-rs or GDB scripts can locate the [`RIOT_RS_THREADS_DEBUG_TABLE`] symbol while the
+//! target is halted, and walk the thread table without relying on Rust debug info.
+use core::{
+    mem::{offset_of, size_of},
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+use crate::{thread::Thread, THREADS, THREADS_NUMOF};
+
+/// Version of the [`DebugTable`] layout, to be bumped whenever the layout changes.
+const DEBUG_TABLE_VERSION: u8 = 1;
+
+/// Descriptor of the thread table.
+///
+/// The `state` field of a [`Thread`] is a [`ThreadState`](crate::ThreadState), whose first byte
+/// is its discriminant (in declaration order, starting from 0).
+///
+/// On RISC-V, the saved stack pointer of a suspended thread lives in its trap frame; `sp` is
+/// only valid until the thread was first scheduled.
+#[repr(C)]
+pub struct DebugTable {
+    /// Layout version of this descriptor.
+    pub version: u8,
+    /// Number of entries of the thread table.
+    pub threads_numof: u8,
+    /// Size (in bytes) of a single entry of the thread table.
+    pub thread_size: u16,
+    /// Offset of the saved stack pointer within a thread entry.
+    pub offset_sp: u16,
+    /// Offset of the thread state within a thread entry.
+    pub offset_state: u16,
+    /// Offset of the priority (`u8`) within a thread entry.
+    pub offset_prio: u16,
+    /// Offset of the thread id (`u8`) within a thread entry.
+    pub offset_pid: u16,
+    /// Offset of the thread flags within a thread entry.
+    pub offset_flags: u16,
+    /// Size (in bytes) of the thread flags.
+    pub flags_size: u16,
+    /// Address of the first entry of the thread table, `0` until threading has been started.
+    pub table: AtomicUsize,
+}
+
+#[no_mangle]
+pub static RIOT_RS_THREADS_DEBUG_TABLE: DebugTable = DebugTable {
+    version: DEBUG_TABLE_VERSION,
+    threads_numof: THREADS_NUMOF as u8,
+    thread_size: size_of::<Thread>() as u16,
+    offset_sp: offset_of!(Thread, sp) as u16,
+    offset_state: offset_of!(Thread, state) as u16,
+    offset_prio: offset_of!(Thread, prio) as u16,
+    offset_pid: offset_of!(Thread, pid) as u16,
+    offset_flags: offset_of!(Thread, flags) as u16,
+    flags_size: size_of::<crate::thread_flags::ThreadFlags>() as u16,
+    table: AtomicUsize::new(0),
+};
+
+/// Publishes the address of the thread table in [`RIOT_RS_THREADS_DEBUG_TABLE`].
+pub(crate) fn publish() {
+    let table = THREADS.with(|threads| threads.threads.as_ptr() as usize);
+    RIOT_RS_THREADS_DEBUG_TABLE
+        .table
+        .store(table, Ordering::Relaxed);
+}
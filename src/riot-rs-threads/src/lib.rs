@@ -1,3 +1,9 @@
+//! Multi-threading for RIOT-rs.
+//!
+//! The scheduler is tickless: it has no periodic timer interrupt, and only runs when the set of
+//! runnable threads changes, i.e., when a thread blocks, gets woken up, or yields.
+//! Threads of equal priority are not time-sliced, and only switch when the running one blocks or
+//! calls [`yield_same()`].
 #![cfg_attr(not(test), no_std)]
 #![feature(naked_functions)]
 #![feature(used_with_arg)]
@@ -7,13 +13,19 @@
 
 mod arch;
 mod autostart_thread;
+mod debug_table;
 mod ensure_once;
 mod thread;
 mod threadlist;
 
 pub mod channel;
+pub mod condvar;
 pub mod lock;
+pub mod mutex;
+pub mod semaphore;
 pub mod thread_flags;
+#[cfg(feature = "tls")]
+pub mod tls;
 
 #[doc(hidden)]
 pub mod macro_reexports {
@@ -23,13 +35,15 @@ pub mod macro_reexports {
     pub use static_cell;
 }
 
+pub use debug_table::{DebugTable, RIOT_RS_THREADS_DEBUG_TABLE};
 pub use riot_rs_runqueue::{RunqueueId, ThreadId};
+pub use thread::ThreadState;
 pub use thread_flags as flags;
 
 use arch::{schedule, Arch, Cpu, ThreadData};
 use ensure_once::EnsureOnce;
 use riot_rs_runqueue::RunQueue;
-use thread::{Thread, ThreadState};
+use thread::Thread;
 
 /// a global defining the number of possible priority levels
 pub const SCHED_PRIO_LEVELS: usize = 12;
@@ -53,6 +67,8 @@ struct Threads {
     /// `Some` when a thread is blocking another thread due to conflicting
     /// resource access.
     thread_blocklist: [Option<ThreadId>; THREADS_NUMOF],
+    /// `Some` when a thread is blocked on a [`mutex::Mutex`], holding the thread that owns it.
+    mutex_owners: [Option<ThreadId>; THREADS_NUMOF],
     /// The currently running thread.
     current_thread: Option<ThreadId>,
 }
@@ -63,6 +79,7 @@ impl Threads {
             runqueue: RunQueue::new(),
             threads: [const { Thread::default() }; THREADS_NUMOF],
             thread_blocklist: [const { None }; THREADS_NUMOF],
+            mutex_owners: [const { None }; THREADS_NUMOF],
             current_thread: None,
         }
     }
@@ -99,8 +116,13 @@ impl Threads {
         if let Some((thread, pid)) = self.get_unused() {
             Cpu::setup_stack(thread, stack, func, arg);
             thread.prio = prio;
+            thread.base_prio = prio;
             thread.pid = pid;
             thread.state = ThreadState::Paused;
+            #[cfg(feature = "tls")]
+            {
+                thread.tls = [0; tls::TLS_SLOTS_NUMOF];
+            }
 
             Some(thread)
         } else {
@@ -163,6 +185,26 @@ impl Threads {
         old_state
     }
 
+    /// Changes the priority of a thread.
+    ///
+    /// If the thread is in the runqueue, it is moved to the runqueue of the new priority.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `thread_id` is >= [`THREADS_NUMOF`].
+    fn set_priority(&mut self, thread_id: ThreadId, prio: RunqueueId) {
+        let thread = &mut self.threads[usize::from(thread_id)];
+        let old_prio = thread.prio;
+        if old_prio == prio {
+            return;
+        }
+        thread.prio = prio;
+        if thread.state == ThreadState::Running {
+            self.runqueue.remove(thread_id, old_prio);
+            self.runqueue.add(thread_id, prio);
+        }
+    }
+
     /// Returns the state of a thread.
     fn get_state(&self, thread_id: ThreadId) -> Option<ThreadState> {
         if self.is_valid_pid(thread_id) {
@@ -185,6 +227,7 @@ impl Threads {
 /// Currently it expects at least:
 /// - Cortex-M: to be called from the reset handler while MSP is active
 pub unsafe fn start_threading() {
+    debug_table::publish();
     Cpu::start_threading();
 }
 
@@ -321,16 +364,142 @@ pub fn wakeup(thread_id: ThreadId) -> bool {
     })
 }
 
+/// Information about a thread, as returned by [`thread_info()`].
+#[derive(Copy, Clone, Debug)]
+pub struct ThreadInfo {
+    /// Id of the thread.
+    pub pid: ThreadId,
+    /// Priority of the thread.
+    pub prio: RunqueueId,
+    /// Current state of the thread.
+    pub state: ThreadState,
+    /// Flags currently set for the thread.
+    pub flags: flags::ThreadFlags,
+    /// Saved stack pointer of the thread.
+    pub sp: usize,
+}
+
+/// Returns information about the thread with id `thread_id`.
+///
+/// Returns `None` if no thread exists for this `thread_id`.
+pub fn thread_info(thread_id: ThreadId) -> Option<ThreadInfo> {
+    THREADS.with(|threads| {
+        if !threads.is_valid_pid(thread_id) {
+            return None;
+        }
+        let thread = &threads.threads[usize::from(thread_id)];
+        Some(ThreadInfo {
+            pid: thread.pid,
+            prio: thread.prio,
+            state: thread.state,
+            flags: thread.flags,
+            sp: thread.sp,
+        })
+    })
+}
+
 /// Returns the size of the internal structure that holds the
 /// a thread's data.
 pub fn thread_struct_size() -> usize {
     core::mem::size_of::<Thread>()
 }
 
+#[cfg(test)]
+pub(crate) mod testing {
+    use std::sync::{Mutex, MutexGuard};
+
+    use super::*;
+
+    // The scheduler state is global, so tests using it must not run concurrently.
+    static SCHEDULER_LOCK: Mutex<()> = Mutex::new(());
+
+    /// Resets the scheduler state to runnable threads `0..prios.len()` with priorities `prios`,
+    /// with thread 0 as the current thread.
+    ///
+    /// The scheduler state must not be used by other tests while the returned guard is alive.
+    pub fn setup(prios: &[u8]) -> MutexGuard<'static, ()> {
+        let guard = SCHEDULER_LOCK
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        THREADS.with_mut(|mut threads| {
+            *threads = Threads::new();
+            for (thread_id, &prio) in prios.iter().enumerate() {
+                let thread_id = ThreadId::new(thread_id as u8);
+                let thread = threads.get_unchecked_mut(thread_id);
+                thread.pid = thread_id;
+                thread.prio = RunqueueId::new(prio);
+                thread.base_prio = RunqueueId::new(prio);
+                threads.set_state(thread_id, ThreadState::Running);
+            }
+            threads.current_thread = Some(ThreadId::new(0));
+        });
+        guard
+    }
+
+    /// Makes `thread_id` the current thread, as if the scheduler switched to it.
+    ///
+    /// Blocking requires the current thread to be the head of its runqueue, so threads that
+    /// block must not share their priority with other runnable threads.
+    pub fn set_current(thread_id: u8) {
+        THREADS.with_mut(|mut threads| threads.current_thread = Some(ThreadId::new(thread_id)));
+    }
+
+    /// Returns the current priority of `thread_id`.
+    pub fn prio(thread_id: u8) -> u8 {
+        let prio = thread_info(ThreadId::new(thread_id)).unwrap().prio;
+        usize::from(prio) as u8
+    }
+
+    /// Returns the state of `thread_id`.
+    pub fn state(thread_id: u8) -> ThreadState {
+        thread_info(ThreadId::new(thread_id)).unwrap().state
+    }
+}
+
 #[cfg(test)]
 mod tests {
+    use super::*;
+    use threadlist::ThreadList;
+
     #[test]
     fn test_basic() {
         assert_eq!(1, 1);
     }
+
+    #[test]
+    fn test_threadlist_pop_by_priority() {
+        let _lock = testing::setup(&[1, 5, 3, 5, 2]);
+        let mut list = ThreadList::new();
+        critical_section::with(|cs| {
+            for thread_id in 1..5 {
+                testing::set_current(thread_id);
+                list.put_current(cs, ThreadState::Paused);
+            }
+
+            // Highest priority first, in blocking order among equal priorities.
+            let popped: Vec<_> = core::iter::from_fn(|| list.pop(cs))
+                .map(|(thread_id, _)| usize::from(thread_id))
+                .collect();
+            assert_eq!(popped, [1, 3, 2, 4]);
+            assert!(list.is_empty(cs));
+        });
+    }
+
+    #[test]
+    fn test_threadlist_priority_change_while_blocked() {
+        let _lock = testing::setup(&[1, 2, 3]);
+        let mut list = ThreadList::new();
+        critical_section::with(|cs| {
+            for thread_id in 1..3 {
+                testing::set_current(thread_id);
+                list.put_current(cs, ThreadState::Paused);
+            }
+            THREADS.with_mut_cs(cs, |mut threads| {
+                threads.set_priority(ThreadId::new(1), RunqueueId::new(4))
+            });
+
+            assert_eq!(list.pop(cs).unwrap().0, ThreadId::new(1));
+            assert_eq!(list.pop(cs).unwrap().0, ThreadId::new(2));
+        });
+    }
 }
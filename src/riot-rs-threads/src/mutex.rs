@@ -0,0 +1,391 @@
+//! This module provides a Mutex with priority inheritance.
+use core::{
+    cell::UnsafeCell,
+    marker::PhantomData,
+    ops::{Deref, DerefMut},
+};
+
+use crate::{
+    threadlist::ThreadList, RunqueueId, ThreadId, ThreadState, Threads, THREADS, THREADS_NUMOF,
+};
+
+/// A mutual exclusion primitive protecting data of type `T`, for use by threads.
+///
+/// While a thread holds mutexes, its priority is raised to the highest priority of the threads
+/// waiting for any of them (priority inheritance), which bounds priority inversion.
+/// Inherited priorities propagate transitively: if the owner is itself blocked on another mutex,
+/// the owner of that mutex inherits the raised priority as well.
+/// Ownership of the mutex is handed over directly to the highest-priority waiter on unlock.
+///
+/// # Limitations
+///
+/// The mutex is not recursive: calling [`Mutex::lock()`] from the thread already holding it
+/// deadlocks that thread, without any error being reported.
+pub struct Mutex<T> {
+    state: UnsafeCell<MutexState>,
+    data: UnsafeCell<T>,
+}
+
+unsafe impl<T: Send> Sync for Mutex<T> {}
+
+enum MutexState {
+    Unlocked,
+    Locked {
+        /// Thread currently holding the mutex.
+        owner: ThreadId,
+        waiters: ThreadList,
+    },
+}
+
+impl<T> Mutex<T> {
+    /// Creates a new **unlocked** [`Mutex`] holding `value`.
+    pub const fn new(value: T) -> Self {
+        Self {
+            state: UnsafeCell::new(MutexState::Unlocked),
+            data: UnsafeCell::new(value),
+        }
+    }
+
+    /// Returns whether the mutex is currently locked.
+    pub fn is_locked(&self) -> bool {
+        critical_section::with(|_| {
+            let state = unsafe { &*self.state.get() };
+            !matches!(state, MutexState::Unlocked)
+        })
+    }
+
+    /// Locks the mutex (blocking).
+    ///
+    /// If the mutex is locked, this blocks the current thread until the mutex is handed over to
+    /// it, raising the priority of the owner if the current thread has a higher priority.
+    /// This deadlocks if the current thread already holds the mutex.
+    ///
+    /// # Panics
+    ///
+    /// Panics if this is called outside of a thread context.
+    pub fn lock(&self) -> MutexGuard<'_, T> {
+        critical_section::with(|cs| {
+            let state = unsafe { &mut *self.state.get() };
+            let current = THREADS.with_cs(cs, |threads| threads.current_pid().unwrap());
+            match state {
+                MutexState::Unlocked => {
+                    *state = MutexState::Locked {
+                        owner: current,
+                        waiters: ThreadList::new(),
+                    }
+                }
+                MutexState::Locked { owner, waiters } => {
+                    THREADS.with_mut_cs(cs, |mut threads| {
+                        threads.mutex_owners[usize::from(current)] = Some(*owner);
+                        update_priority(&mut threads, *owner);
+                    });
+                    // The mutex is handed over to this thread before it gets woken up.
+                    waiters.put_current(cs, ThreadState::LockBlocked);
+                }
+            }
+        });
+        MutexGuard::new(self)
+    }
+
+    /// Locks the mutex (non-blocking).
+    ///
+    /// Returns `None` if the mutex is already locked.
+    ///
+    /// # Panics
+    ///
+    /// Panics if this is called outside of a thread context.
+    pub fn try_lock(&self) -> Option<MutexGuard<'_, T>> {
+        critical_section::with(|cs| {
+            let state = unsafe { &mut *self.state.get() };
+            match state {
+                MutexState::Unlocked => {
+                    let owner = THREADS.with_cs(cs, |threads| threads.current_pid().unwrap());
+                    *state = MutexState::Locked {
+                        owner,
+                        waiters: ThreadList::new(),
+                    };
+                    Some(MutexGuard::new(self))
+                }
+                MutexState::Locked { .. } => None,
+            }
+        })
+    }
+
+    /// Returns a mutable reference to the protected data.
+    ///
+    /// No locking is needed, as the mutable borrow guarantees exclusive access.
+    pub fn get_mut(&mut self) -> &mut T {
+        self.data.get_mut()
+    }
+
+    /// Unlocks the mutex.
+    ///
+    /// If there are waiters, ownership is handed over to the highest-priority waiter, which
+    /// inherits the priorities of the remaining waiters.
+    /// The priority of the previous owner is lowered to what it still inherits through the other
+    /// mutexes it holds, or to its base priority.
+    fn unlock(&self) {
+        critical_section::with(|cs| {
+            let state = unsafe { &mut *self.state.get() };
+            let MutexState::Locked { owner, waiters } = state else {
+                unreachable!("unlocking an unlocked mutex");
+            };
+            let previous_owner = *owner;
+
+            match waiters.pop(cs) {
+                Some((next_owner, _)) => {
+                    *owner = next_owner;
+                    THREADS.with_mut_cs(cs, |mut threads| {
+                        threads.mutex_owners[usize::from(next_owner)] = None;
+                        let mut next = waiters.head;
+                        while let Some(waiter) = next {
+                            threads.mutex_owners[usize::from(waiter)] = Some(next_owner);
+                            next = threads.thread_blocklist[usize::from(waiter)];
+                        }
+                        update_priority(&mut threads, next_owner);
+                    });
+                }
+                None => *state = MutexState::Unlocked,
+            }
+            THREADS.with_mut_cs(cs, |mut threads| {
+                update_priority(&mut threads, previous_owner)
+            });
+            crate::schedule();
+        })
+    }
+}
+
+/// Recomputes the priority of `thread_id` from the threads waiting for the mutexes it holds.
+///
+/// If the priority changes while the thread is itself blocked on a mutex, the owner of that mutex
+/// is updated as well, transitively.
+fn update_priority(threads: &mut Threads, mut thread_id: ThreadId) {
+    loop {
+        let base_prio = threads.get_unchecked_mut(thread_id).base_prio;
+        let prio = (0..THREADS_NUMOF)
+            .filter(|&waiter| threads.mutex_owners[waiter] == Some(thread_id))
+            .map(|waiter| threads.threads[waiter].prio)
+            .fold(base_prio, RunqueueId::max);
+        if prio == threads.get_unchecked_mut(thread_id).prio {
+            return;
+        }
+        threads.set_priority(thread_id, prio);
+
+        match threads.mutex_owners[usize::from(thread_id)] {
+            Some(owner) => thread_id = owner,
+            None => return,
+        }
+    }
+}
+
+impl<T: Default> Default for Mutex<T> {
+    fn default() -> Self {
+        Self::new(T::default())
+    }
+}
+
+/// Grants access to the data protected by a [`Mutex`], unlocking it when dropped.
+///
+/// The guard cannot be sent to another thread, as the mutex must be unlocked by its owner.
+pub struct MutexGuard<'a, T> {
+    mutex: &'a Mutex<T>,
+    // Makes the guard `!Send`.
+    _not_send: PhantomData<*const ()>,
+}
+
+// SAFETY: sharing the guard only gives access to `&T`.
+unsafe impl<T: Sync> Sync for MutexGuard<'_, T> {}
+
+impl<'a, T> MutexGuard<'a, T> {
+    fn new(mutex: &'a Mutex<T>) -> Self {
+        Self {
+            mutex,
+            _not_send: PhantomData,
+        }
+    }
+
+    /// Consumes the guard, unlocking the mutex, and returns the mutex.
+    pub(crate) fn into_mutex(self) -> &'a Mutex<T> {
+        let mutex = self.mutex;
+        core::mem::forget(self);
+        mutex.unlock();
+        mutex
+    }
+}
+
+impl<T> Deref for MutexGuard<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        // SAFETY: the guard guarantees exclusive access to the data.
+        unsafe { &*self.mutex.data.get() }
+    }
+}
+
+impl<T> DerefMut for MutexGuard<'_, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        // SAFETY: the guard guarantees exclusive access to the data.
+        unsafe { &mut *self.mutex.data.get() }
+    }
+}
+
+impl<T> Drop for MutexGuard<'_, T> {
+    fn drop(&mut self) {
+        self.mutex.unlock();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{prio, set_current, setup, state};
+
+    // Locking never blocks in tests: the guard is returned as soon as the current thread has
+    // been put into the waiters, and must only be dropped once the mutex was handed over.
+
+    fn owner<T>(mutex: &Mutex<T>) -> Option<ThreadId> {
+        critical_section::with(|_| match unsafe { &*mutex.state.get() } {
+            MutexState::Unlocked => None,
+            MutexState::Locked { owner, .. } => Some(*owner),
+        })
+    }
+
+    #[test]
+    fn test_lock_unlock() {
+        let _lock = setup(&[1]);
+        let mutex = Mutex::new(0);
+
+        let mut guard = mutex.lock();
+        *guard += 1;
+        assert!(mutex.is_locked());
+        assert!(mutex.try_lock().is_none());
+        drop(guard);
+
+        assert!(!mutex.is_locked());
+        assert_eq!(*mutex.try_lock().unwrap(), 1);
+        assert!(!mutex.is_locked());
+    }
+
+    #[test]
+    fn test_handover() {
+        let _lock = setup(&[1, 2]);
+        let mutex = Mutex::new(());
+
+        let guard0 = mutex.lock();
+        set_current(1);
+        let guard1 = mutex.lock();
+        assert_eq!(state(1), ThreadState::LockBlocked);
+
+        // Unlocking hands the mutex over to the waiter instead of unlocking it.
+        set_current(0);
+        drop(guard0);
+        assert_eq!(state(1), ThreadState::Running);
+        assert_eq!(owner(&mutex), Some(ThreadId::new(1)));
+
+        set_current(1);
+        drop(guard1);
+        assert_eq!(owner(&mutex), None);
+    }
+
+    #[test]
+    fn test_priority_inheritance() {
+        let _lock = setup(&[1, 5, 3]);
+        let mutex = Mutex::new(());
+
+        let guard0 = mutex.lock();
+
+        // The owner inherits the priority of higher-priority waiters only.
+        set_current(1);
+        let guard1 = mutex.lock();
+        assert_eq!(prio(0), 5);
+        set_current(2);
+        let guard2 = mutex.lock();
+        assert_eq!(prio(0), 5);
+
+        // The owner gets its priority back, and the highest-priority waiter becomes the next
+        // owner.
+        set_current(0);
+        drop(guard0);
+        assert_eq!(prio(0), 1);
+        assert_eq!(owner(&mutex), Some(ThreadId::new(1)));
+        assert_eq!(state(1), ThreadState::Running);
+        assert_eq!(state(2), ThreadState::LockBlocked);
+
+        set_current(1);
+        drop(guard1);
+        assert_eq!(prio(1), 5);
+        assert_eq!(owner(&mutex), Some(ThreadId::new(2)));
+        assert_eq!(state(2), ThreadState::Running);
+        assert_eq!(prio(2), 3);
+
+        set_current(2);
+        drop(guard2);
+        assert_eq!(owner(&mutex), None);
+    }
+
+    #[test]
+    fn test_nested_mutexes() {
+        let _lock = setup(&[1, 4, 3]);
+        let mutex_a = Mutex::new(());
+        let mutex_b = Mutex::new(());
+
+        let guard_a = mutex_a.lock();
+        let guard_b = mutex_b.lock();
+        set_current(1);
+        let guard1 = mutex_a.lock();
+        set_current(2);
+        let guard2 = mutex_b.lock();
+        assert_eq!(prio(0), 4);
+
+        // Unlocking one mutex keeps the priority inherited through the other one.
+        set_current(0);
+        drop(guard_a);
+        assert_eq!(prio(0), 3);
+        drop(guard_b);
+        assert_eq!(prio(0), 1);
+
+        set_current(1);
+        drop(guard1);
+        set_current(2);
+        drop(guard2);
+        assert!(!mutex_a.is_locked());
+        assert!(!mutex_b.is_locked());
+    }
+
+    #[test]
+    fn test_transitive_priority_inheritance() {
+        let _lock = setup(&[1, 2, 5]);
+        let mutex_a = Mutex::new(());
+        let mutex_b = Mutex::new(());
+
+        // Thread 1 holds B and waits for A, held by thread 0.
+        let guard_a = mutex_a.lock();
+        set_current(1);
+        let guard_b = mutex_b.lock();
+        let guard1 = mutex_a.lock();
+        assert_eq!(prio(0), 2);
+
+        // Thread 2 waiting for B raises the priority of both thread 1 and thread 0.
+        set_current(2);
+        let guard2 = mutex_b.lock();
+        assert_eq!(prio(1), 5);
+        assert_eq!(prio(0), 5);
+
+        set_current(0);
+        drop(guard_a);
+        assert_eq!(prio(0), 1);
+        assert_eq!(owner(&mutex_a), Some(ThreadId::new(1)));
+        assert_eq!(prio(1), 5);
+
+        set_current(1);
+        drop(guard_b);
+        assert_eq!(prio(1), 2);
+        assert_eq!(owner(&mutex_b), Some(ThreadId::new(2)));
+        drop(guard1);
+
+        set_current(2);
+        drop(guard2);
+        assert!(!mutex_a.is_locked());
+        assert!(!mutex_b.is_locked());
+    }
+}
@@ -0,0 +1,136 @@
+//! This module provides a counting semaphore.
+use core::cell::UnsafeCell;
+
+use crate::{threadlist::ThreadList, ThreadState};
+
+/// A counting semaphore.
+///
+/// Posting can be done from ISRs, e.g., to signal events to a thread.
+/// When threads are waiting, posting hands the permit over to the highest-priority waiter
+/// directly.
+pub struct Semaphore {
+    state: UnsafeCell<SemaphoreState>,
+}
+
+unsafe impl Sync for Semaphore {}
+
+struct SemaphoreState {
+    count: usize,
+    waiters: ThreadList,
+}
+
+impl Semaphore {
+    /// Creates a new [`Semaphore`] holding `count` permits.
+    pub const fn new(count: usize) -> Self {
+        Self {
+            state: UnsafeCell::new(SemaphoreState {
+                count,
+                waiters: ThreadList::new(),
+            }),
+        }
+    }
+
+    /// Returns the number of currently available permits.
+    pub fn count(&self) -> usize {
+        critical_section::with(|_| {
+            let state = unsafe { &*self.state.get() };
+            state.count
+        })
+    }
+
+    /// Takes a permit (blocking).
+    ///
+    /// If no permit is available, this blocks the current thread until the semaphore is posted.
+    ///
+    /// # Panics
+    ///
+    /// Panics if this is called outside of a thread context while no permit is available.
+    pub fn wait(&self) {
+        critical_section::with(|cs| {
+            let state = unsafe { &mut *self.state.get() };
+            if state.count > 0 {
+                state.count -= 1;
+            } else {
+                state.waiters.put_current(cs, ThreadState::SemaphoreBlocked);
+            }
+        })
+    }
+
+    /// Takes a permit (non-blocking).
+    ///
+    /// Returns `false` if no permit is available.
+    pub fn try_wait(&self) -> bool {
+        critical_section::with(|_| {
+            let state = unsafe { &mut *self.state.get() };
+            if state.count > 0 {
+                state.count -= 1;
+                true
+            } else {
+                false
+            }
+        })
+    }
+
+    /// Releases a permit.
+    ///
+    /// If threads are waiting, the highest-priority waiter is woken up and takes the permit.
+    /// The number of permits saturates at [`usize::MAX`].
+    pub fn post(&self) {
+        critical_section::with(|cs| {
+            let state = unsafe { &mut *self.state.get() };
+            if state.waiters.pop(cs).is_none() {
+                state.count = state.count.saturating_add(1);
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{set_current, setup, state};
+
+    #[test]
+    fn test_counting() {
+        let _lock = setup(&[1]);
+        let semaphore = Semaphore::new(2);
+
+        semaphore.wait();
+        assert_eq!(semaphore.count(), 1);
+        assert!(semaphore.try_wait());
+        assert!(!semaphore.try_wait());
+        assert_eq!(semaphore.count(), 0);
+
+        semaphore.post();
+        semaphore.post();
+        assert_eq!(semaphore.count(), 2);
+        assert_eq!(state(0), ThreadState::Running);
+    }
+
+    #[test]
+    fn test_post_wakes_up_waiters() {
+        let _lock = setup(&[1, 2, 3]);
+        let semaphore = Semaphore::new(0);
+
+        set_current(1);
+        semaphore.wait();
+        set_current(2);
+        semaphore.wait();
+        assert_eq!(state(1), ThreadState::SemaphoreBlocked);
+        assert_eq!(state(2), ThreadState::SemaphoreBlocked);
+
+        // Each post hands its permit over to a waiter, starting with the highest-priority one.
+        set_current(0);
+        semaphore.post();
+        assert_eq!(state(2), ThreadState::Running);
+        assert_eq!(state(1), ThreadState::SemaphoreBlocked);
+        assert_eq!(semaphore.count(), 0);
+
+        semaphore.post();
+        assert_eq!(state(1), ThreadState::Running);
+        assert_eq!(semaphore.count(), 0);
+
+        semaphore.post();
+        assert_eq!(semaphore.count(), 1);
+    }
+}
@@ -1,3 +1,5 @@
+#[cfg(feature = "tls")]
+use crate::tls::TLS_SLOTS_NUMOF;
 use crate::{thread_flags::ThreadFlags, Arch, Cpu, RunqueueId, ThreadData, ThreadId};
 
 /// Main struct for holding thread data.
@@ -10,18 +12,26 @@ pub struct Thread {
     /// Priority of the thread between 0..[`super::SCHED_PRIO_LEVELS`].
     /// Multiple threads may have the same priority.
     pub prio: RunqueueId,
+    /// Priority the thread was created with, i.e., without priorities inherited through
+    /// [`super::mutex::Mutex`]es.
+    pub base_prio: RunqueueId,
     /// Id of the thread between 0..[`super::THREADS_NUMOF`].
     /// Ids are unique while a thread is alive but reused after a thread finished.
     pub pid: ThreadId,
     /// Flags set for the thread.
     pub flags: ThreadFlags,
+    /// Thread-local storage slots, see [`super::tls`].
+    #[cfg(feature = "tls")]
+    pub tls: [usize; TLS_SLOTS_NUMOF],
     /// Arch-specific thread data.
     #[allow(dead_code)]
     pub(crate) data: ThreadData,
 }
 
 /// Possible states of a thread
+// `repr(u8)` makes the discriminant the first byte, for external debuggers.
 #[derive(Copy, Clone, PartialEq, Debug)]
+#[repr(u8)]
 pub enum ThreadState {
     /// No active thread.
     Invalid,
@@ -32,7 +42,7 @@ pub enum ThreadState {
     Running,
     /// Suspended / paused.
     Paused,
-    /// Waiting to acquire a [`super::lock::Lock`].
+    /// Waiting to acquire a [`super::lock::Lock`] or a [`super::mutex::Mutex`].
     LockBlocked,
     /// Waiting for [`ThreadFlags`] to be set.
     FlagBlocked(crate::thread_flags::WaitMode),
@@ -40,6 +50,10 @@ pub enum ThreadState {
     ChannelRxBlocked(usize),
     /// Waiting to send on a [`super::channel::Channel`], i.e. waiting for the receiver.
     ChannelTxBlocked(usize),
+    /// Waiting for a [`super::semaphore::Semaphore`] to be posted.
+    SemaphoreBlocked,
+    /// Waiting for a [`super::condvar::Condvar`] to be notified.
+    CondvarBlocked,
 }
 
 impl Thread {
@@ -50,7 +64,10 @@ impl Thread {
             state: ThreadState::Invalid,
             data: Cpu::DEFAULT_THREAD_DATA,
             flags: 0,
+            #[cfg(feature = "tls")]
+            tls: [0; TLS_SLOTS_NUMOF],
             prio: RunqueueId::new(0),
+            base_prio: RunqueueId::new(0),
             pid: ThreadId::new(0),
         }
     }
@@ -1,11 +1,16 @@
 use critical_section::CriticalSection;
 
-use crate::{ThreadId, ThreadState, THREADS};
+use crate::{ThreadId, ThreadState, Threads, THREADS};
 
 /// Manages blocked [`super::Thread`]s for a resource, and triggering the scheduler when needed.
+///
+/// Threads are woken up by priority, and in the order they blocked among threads of equal
+/// priority.
+/// The priority is only compared when waking up a thread, so that threads whose priority changed
+/// while blocked (e.g., through priority inheritance) are still woken up in priority order.
 #[derive(Debug, Default)]
 pub struct ThreadList {
-    /// Next thread to run once the resource is available.
+    /// First thread that blocked on the resource.
     pub head: Option<ThreadId>,
 }
 
@@ -19,31 +24,52 @@ impl ThreadList {
     pub fn put_current(&mut self, cs: CriticalSection, state: ThreadState) {
         THREADS.with_mut_cs(cs, |mut threads| {
             let thread_id = threads.current_thread.unwrap();
-            threads.thread_blocklist[usize::from(thread_id)] = self.head;
-            self.head = Some(thread_id);
+            threads.thread_blocklist[usize::from(thread_id)] = None;
+            let tail = self.thread_ids(&threads).last();
+            match tail {
+                Some(tail) => threads.thread_blocklist[usize::from(tail)] = Some(thread_id),
+                None => self.head = Some(thread_id),
+            }
             threads.set_state(thread_id, state);
             crate::schedule();
         });
     }
 
-    /// Removes the head from this [`ThreadList`].
+    /// Removes the highest-priority thread from this [`ThreadList`].
     ///
     /// Sets the thread's [`ThreadState`] to [`ThreadState::Running`] and triggers
     /// the scheduler.
     ///
     /// Returns the thread's [`ThreadId`] and its previous [`ThreadState`].
     pub fn pop(&mut self, cs: CriticalSection) -> Option<(ThreadId, ThreadState)> {
-        if let Some(head) = self.head {
-            let old_state = THREADS.with_mut_cs(cs, |mut threads| {
-                self.head = threads.thread_blocklist[usize::from(head)].take();
-                let old_state = threads.set_state(head, ThreadState::Running);
-                crate::schedule();
-                old_state
-            });
-            Some((head, old_state))
-        } else {
-            None
-        }
+        THREADS.with_mut_cs(cs, |mut threads| {
+            let mut next = None;
+            let mut prev = None;
+            for thread_id in self.thread_ids(&threads) {
+                let prio = threads.threads[usize::from(thread_id)].prio;
+                if next.map_or(true, |(next_prio, _, _)| prio > next_prio) {
+                    next = Some((prio, thread_id, prev));
+                }
+                prev = Some(thread_id);
+            }
+            let (_, thread_id, prev) = next?;
+
+            let successor = threads.thread_blocklist[usize::from(thread_id)].take();
+            match prev {
+                Some(prev) => threads.thread_blocklist[usize::from(prev)] = successor,
+                None => self.head = successor,
+            }
+            let old_state = threads.set_state(thread_id, ThreadState::Running);
+            crate::schedule();
+            Some((thread_id, old_state))
+        })
+    }
+
+    /// Returns an iterator over the threads in this [`ThreadList`], in the order they blocked.
+    fn thread_ids<'a>(&self, threads: &'a Threads) -> impl Iterator<Item = ThreadId> + 'a {
+        core::iter::successors(self.head, |thread_id| {
+            threads.thread_blocklist[usize::from(*thread_id)]
+        })
     }
 
     /// Determines if this [`ThreadList`] is empty.
@@ -0,0 +1,74 @@
+//! Thread-local storage.
+//!
+//! Each thread has [`TLS_SLOTS_NUMOF`] slots, each holding a `usize`, e.g., a pointer to
+//! `'static` data or an index.
+//! The number of slots can be set with the `CONFIG_TLS_SLOTS_NUMOF` environment variable.
+//! Slots are zeroed when a thread is created.
+//! How slots are assigned is up to the application.
+use crate::THREADS;
+
+/// Number of thread-local storage slots of each thread.
+pub const TLS_SLOTS_NUMOF: usize = riot_rs_utils::usize_from_env_or!(
+    "CONFIG_TLS_SLOTS_NUMOF",
+    4,
+    "number of thread-local storage slots of each thread"
+);
+
+/// Sets the value of `slot` for the current thread.
+///
+/// # Panics
+///
+/// Panics if this is called outside of a thread context.
+/// Panics if `slot` is >= [`TLS_SLOTS_NUMOF`].
+pub fn set(slot: usize, value: usize) {
+    THREADS.with_mut(|mut threads| threads.current().unwrap().tls[slot] = value)
+}
+
+/// Returns the value of `slot` for the current thread.
+///
+/// # Panics
+///
+/// Panics if this is called outside of a thread context.
+/// Panics if `slot` is >= [`TLS_SLOTS_NUMOF`].
+pub fn get(slot: usize) -> usize {
+    THREADS.with(|threads| {
+        let thread_id = threads.current_pid().unwrap();
+        threads.threads[usize::from(thread_id)].tls[slot]
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{set_current, setup};
+
+    #[test]
+    fn test_per_thread_slots() {
+        let _lock = setup(&[1, 2]);
+
+        set(0, 10);
+        set(TLS_SLOTS_NUMOF - 1, 11);
+        set_current(1);
+        assert_eq!(get(0), 0);
+        set(0, 20);
+
+        assert_eq!(get(0), 20);
+        set_current(0);
+        assert_eq!(get(0), 10);
+        assert_eq!(get(TLS_SLOTS_NUMOF - 1), 11);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_set_out_of_range() {
+        let _lock = setup(&[1]);
+        set(TLS_SLOTS_NUMOF, 0);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_get_out_of_range() {
+        let _lock = setup(&[1]);
+        get(TLS_SLOTS_NUMOF);
+    }
+}
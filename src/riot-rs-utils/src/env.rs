@@ -7,7 +7,7 @@ macro_rules! define_env_with_default_macro {
             // $doc is currently unused
             ($env_var:literal, $default:expr, $doc:literal) => {
                 if let Some(str_value) = option_env!($env_var) {
-                    if let Ok(value) = $crate::env::konst::primitive::$parse_fn_name(str_value) {
+                    if let Some(value) = $crate::env::$parse_fn_name(str_value) {
                         value
                     } else {
                         $crate::env::const_panic::concat_panic!(
@@ -29,6 +29,36 @@ macro_rules! define_env_with_default_macro {
 
 define_env_with_default_macro!(usize_from_env_or, parse_usize, "a usize");
 
+/// Parses a `usize`, either in decimal, or in hexadecimal when prefixed with `0x`.
+#[doc(hidden)]
+pub const fn parse_usize(str_value: &str) -> Option<usize> {
+    let mut digits = match str_value.as_bytes() {
+        [b'0', b'x' | b'X', digits @ ..] if !digits.is_empty() => digits,
+        _ => {
+            return match konst::primitive::parse_usize(str_value) {
+                Ok(value) => Some(value),
+                Err(_) => None,
+            }
+        }
+    };
+
+    let mut value: usize = 0;
+    while let [digit, rest @ ..] = digits {
+        let digit = match digit {
+            b'0'..=b'9' => *digit - b'0',
+            b'a'..=b'f' => *digit - b'a' + 10,
+            b'A'..=b'F' => *digit - b'A' + 10,
+            _ => return None,
+        };
+        value = match value.checked_mul(16) {
+            Some(value) => value + digit as usize,
+            None => return None,
+        };
+        digits = rest;
+    }
+    Some(value)
+}
+
 #[macro_export]
 macro_rules! str_from_env_or {
     // $doc is currently unused
@@ -40,3 +70,18 @@ macro_rules! str_from_env_or {
         }
     };
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_usize() {
+        assert_eq!(parse_usize("1234"), Some(1234));
+        assert_eq!(parse_usize("0x1209"), Some(0x1209));
+        assert_eq!(parse_usize("0XcAfE"), Some(0xcafe));
+        assert_eq!(parse_usize("0x"), None);
+        assert_eq!(parse_usize("0xg"), None);
+        assert_eq!(parse_usize("12a"), None);
+    }
+}
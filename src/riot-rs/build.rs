@@ -0,0 +1,40 @@
+use std::env;
+use std::fs;
+use std::path::Path;
+
+/// Returns the values of a `--cfg name="value"` configuration option, as seen by build scripts.
+fn cfg_values(name: &str) -> Vec<String> {
+    env::var(format!("CARGO_CFG_{}", name.to_uppercase()))
+        .map(|values| values.split(',').map(str::to_owned).collect())
+        .unwrap_or_default()
+}
+
+fn str_slice(values: &[String]) -> String {
+    let values: Vec<_> = values.iter().map(|value| format!("{value:?}")).collect();
+    format!("&[{}]", values.join(", "))
+}
+
+fn main() {
+    let out_dir = env::var_os("OUT_DIR").unwrap();
+    let dest_path = Path::new(&out_dir).join("buildinfo.rs");
+
+    // Unlike `CARGO_FEATURE_*`, this has the actual feature names, which may contain `-` or `_`.
+    let mut enabled_features = cfg_values("feature");
+    enabled_features.sort();
+
+    let manifest = format!(
+        "/// Contexts (from laze) the application is built for.\n\
+         pub const CONTEXTS: &[&str] = {};\n\
+         /// Capabilities (from laze) of the board the application is built for.\n\
+         pub const CAPABILITIES: &[&str] = {};\n\
+         /// Cargo features enabled on the `riot-rs` crate.\n\
+         pub const FEATURES: &[&str] = {};\n",
+        str_slice(&cfg_values("context")),
+        str_slice(&cfg_values("capability")),
+        str_slice(&enabled_features),
+    );
+
+    fs::write(&dest_path, manifest.as_bytes()).expect("write failed");
+
+    println!("cargo::rerun-if-changed=build.rs");
+}
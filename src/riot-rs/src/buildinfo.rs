@@ -1,4 +1,9 @@
 //! Exposes information about the build.
+//!
+//! Besides the board name, this module exposes a manifest of the contexts, capabilities and
+//! Cargo features the application is built with.
+//! This allows checking them in `const` contexts, e.g., in code generated by RIOT-rs macros,
+//! which cannot rely on `cfg` attributes of the crate they are used in.
 
 /// The board name.
 ///
@@ -9,3 +14,74 @@ pub const BOARD: &str = riot_rs_utils::str_from_env_or!(
     "unknown",
     "board name provided by the build system"
 );
+
+include!(concat!(env!("OUT_DIR"), "/buildinfo.rs"));
+
+/// Returns whether the application is built for `context`.
+pub const fn has_context(context: &str) -> bool {
+    contains(CONTEXTS, context)
+}
+
+/// Returns whether the board provides `capability` (e.g., `hw/usb-device-port`).
+pub const fn has_capability(capability: &str) -> bool {
+    contains(CAPABILITIES, capability)
+}
+
+/// Returns whether the `feature` Cargo feature is enabled on the `riot-rs` crate.
+pub const fn has_feature(feature: &str) -> bool {
+    contains(FEATURES, feature)
+}
+
+const fn contains(mut list: &[&str], value: &str) -> bool {
+    while let Some((first, rest)) = list.split_first() {
+        if str_eq(first, value) {
+            return true;
+        }
+        list = rest;
+    }
+    false
+}
+
+const fn str_eq(a: &str, b: &str) -> bool {
+    let (mut a, mut b) = (a.as_bytes(), b.as_bytes());
+    if a.len() != b.len() {
+        return false;
+    }
+    while let (Some((a_first, a_rest)), Some((b_first, b_rest))) =
+        (a.split_first(), b.split_first())
+    {
+        if *a_first != *b_first {
+            return false;
+        }
+        (a, b) = (a_rest, b_rest);
+    }
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // The helpers must be usable in `const` contexts, as in the code generated by the macros.
+    const _: () = assert!(contains(&["usb"], "usb") && !str_eq("usb", "net"));
+
+    #[test]
+    fn test_str_eq() {
+        assert!(str_eq("", ""));
+        assert!(str_eq("usb", "usb"));
+        assert!(!str_eq("usb", "usb-ethernet"));
+        assert!(!str_eq("usb", "net"));
+        assert!(!str_eq("usb", "usc"));
+        assert!(!str_eq("", "usb"));
+    }
+
+    #[test]
+    fn test_contains() {
+        const LIST: &[&str] = &["net", "usb", "usb-ethernet"];
+        assert!(contains(LIST, "net"));
+        assert!(contains(LIST, "usb-ethernet"));
+        assert!(!contains(LIST, "usb-eth"));
+        assert!(!contains(LIST, "wifi"));
+        assert!(!contains(&[], "usb"));
+    }
+}
@@ -29,6 +29,8 @@ pub use riot_rs_rt as rt;
 #[cfg(feature = "threading")]
 #[doc(inline)]
 pub use riot_rs_threads as thread;
+#[doc(inline)]
+pub use riot_rs_utils as utils;
 
 // Attribute macros
 pub use riot_rs_macros::config;
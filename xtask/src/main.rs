@@ -0,0 +1,191 @@
+//! Maintenance tasks for RIOT-rs, run with `cargo xtask <task>`.
+//!
+//! # Tasks
+//!
+//! - `matrix`: builds the `riot-rs` crate for each builder (and thus each laze context), alone
+//!   and with each of the given Cargo features enabled, and reports which combinations fail to
+//!   build. The compiler output of failed builds is printed.
+//!
+//!   ```text
+//!   cargo xtask matrix [--builders <b1,b2,...>] [--features <f1,f2,...>]
+//!   ```
+//!
+//!   `--builders` defaults to the builders tested in CI, `--features` to the documented features
+//!   of `riot-rs`.
+//!
+//! - `builders`: prints the builders tested in CI, separated by commas, as expected by laze's
+//!   `LAZE_BUILDERS` environment variable.
+
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+    process::{Command, ExitCode, Output},
+};
+
+/// laze application whose environment (target, `RUSTFLAGS`, ...) is used to build `riot-rs`.
+const LAZE_APP: &str = "examples/minimal";
+
+// Builder names as defined in `laze-project.yml` (including the `expressif` spelling).
+// CI takes its `LAZE_BUILDERS` from this list, through the `builders` task.
+const DEFAULT_BUILDERS: &[&str] = &[
+    "ai-c3",
+    "expressif-esp32-c6-devkitc-1",
+    "microbit-v2",
+    "nrf52840dk",
+    "nrf5340dk",
+    "rpi-pico",
+    "rpi-pico-w",
+];
+
+// Documented features that are not meant to be enabled alongside a board.
+const EXCLUDED_FEATURES: &[&str] = &["no-boards"];
+
+const USAGE: &str = "usage: cargo xtask matrix [--builders <list>] [--features <list>]
+       cargo xtask builders";
+
+fn main() -> ExitCode {
+    let mut args = env::args().skip(1);
+    let result = match args.next().as_deref() {
+        Some("matrix") => MatrixArgs::parse(args).and_then(|matrix_args| matrix(&matrix_args)),
+        Some("builders") => {
+            println!("{}", DEFAULT_BUILDERS.join(","));
+            Ok(ExitCode::SUCCESS)
+        }
+        _ => Err(USAGE.to_string()),
+    };
+
+    result.unwrap_or_else(|err| {
+        eprintln!("error: {err}");
+        ExitCode::FAILURE
+    })
+}
+
+struct MatrixArgs {
+    builders: Vec<String>,
+    features: Vec<String>,
+}
+
+impl MatrixArgs {
+    fn parse(mut args: impl Iterator<Item = String>) -> Result<Self, String> {
+        let mut builders = None;
+        let mut features = None;
+
+        while let Some(arg) = args.next() {
+            let value = args
+                .next()
+                .ok_or_else(|| format!("missing value for `{arg}`"))?;
+            match arg.as_str() {
+                "--builders" => builders = Some(split_list(&value)),
+                "--features" => features = Some(split_list(&value)),
+                _ => return Err(format!("unknown argument `{arg}`")),
+            }
+        }
+
+        Ok(Self {
+            builders: builders
+                .unwrap_or_else(|| DEFAULT_BUILDERS.iter().map(ToString::to_string).collect()),
+            features: match features {
+                Some(features) => features,
+                None => documented_features()?,
+            },
+        })
+    }
+}
+
+fn workspace_root() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .parent()
+        .unwrap()
+        .to_path_buf()
+}
+
+fn split_list(list: &str) -> Vec<String> {
+    list.split(',')
+        .map(str::trim)
+        .filter(|item| !item.is_empty())
+        .map(ToString::to_string)
+        .collect()
+}
+
+/// Returns the features of `riot-rs` documented in its manifest (with a `##` comment), which
+/// excludes the internal ones.
+fn documented_features() -> Result<Vec<String>, String> {
+    let manifest_path = workspace_root().join("src/riot-rs/Cargo.toml");
+    let manifest = fs::read_to_string(&manifest_path)
+        .map_err(|err| format!("failed to read {}: {err}", manifest_path.display()))?;
+
+    let mut features = Vec::new();
+    let mut documented = false;
+    for line in manifest
+        .lines()
+        .skip_while(|line| line.trim() != "[features]")
+        .skip(1)
+        .take_while(|line| !line.starts_with('['))
+    {
+        let line = line.trim();
+        if line.starts_with("## ") || line == "##" {
+            documented = true;
+        } else if let Some((feature, _)) = line.split_once('=') {
+            let feature = feature.trim();
+            if documented && !EXCLUDED_FEATURES.contains(&feature) {
+                features.push(feature.to_string());
+            }
+            documented = false;
+        }
+    }
+
+    Ok(features)
+}
+
+fn matrix(args: &MatrixArgs) -> Result<ExitCode, String> {
+    // Each builder is built without additional features, then with each feature.
+    let feature_sets = std::iter::once(None)
+        .chain(args.features.iter().map(Some))
+        .collect::<Vec<_>>();
+
+    let mut failures = Vec::new();
+    for builder in &args.builders {
+        for feature in &feature_sets {
+            let combination = match feature {
+                Some(feature) => format!("{builder} + {feature}"),
+                None => builder.clone(),
+            };
+            println!("building {combination}...");
+
+            let output = build(builder, feature.map(String::as_str))?;
+            if !output.status.success() {
+                println!("{}", String::from_utf8_lossy(&output.stdout));
+                eprintln!("{}", String::from_utf8_lossy(&output.stderr));
+                failures.push(combination);
+            }
+        }
+    }
+
+    let total = args.builders.len() * feature_sets.len();
+    println!("{} of {total} combinations built", total - failures.len());
+    if failures.is_empty() {
+        Ok(ExitCode::SUCCESS)
+    } else {
+        println!("failed combinations:");
+        for failure in &failures {
+            println!("  {failure}");
+        }
+        Ok(ExitCode::FAILURE)
+    }
+}
+
+/// Builds `riot-rs` for `builder` with laze, optionally enabling `feature`.
+fn build(builder: &str, feature: Option<&str>) -> Result<Output, String> {
+    let mut command = Command::new("laze");
+    command
+        .arg("-C")
+        .arg(workspace_root().join(LAZE_APP))
+        .args(["task", "-b", builder, "build-riot-rs"]);
+    if let Some(feature) = feature {
+        command.arg(format!("--features={feature}"));
+    }
+
+    command
+        .output()
+        .map_err(|err| format!("failed to run laze: {err}"))
+}